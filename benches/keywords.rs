@@ -0,0 +1,86 @@
+use anitomy::{Keyword, KeywordSet};
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde::Deserialize;
+use std::hint::black_box;
+
+#[derive(Deserialize)]
+struct InputData {
+    input: String,
+}
+
+fn inputs() -> Vec<String> {
+    let data = include_str!("../tests/data.json");
+    let tests: Vec<InputData> = serde_json::from_str(data).expect("could not parse JSON");
+    tests.into_iter().map(|t| t.input).collect()
+}
+
+/// The previous implementation, which checked every key against every prefix of the input.
+fn linear_longest_prefix<'a>(
+    keys: &[(&str, Keyword)],
+    input: &'a str,
+) -> Option<(&'a str, Keyword)> {
+    let mut found = None;
+    for (index, ch) in input.char_indices() {
+        let prefix = &input[0..(index + ch.len_utf8())];
+        if let Some((_, keyword)) = keys
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(prefix))
+        {
+            found = Some((prefix, *keyword));
+        }
+        let has_prefix = keys.iter().any(|(key, _)| {
+            key.get(..prefix.len())
+                .is_some_and(|k| k.eq_ignore_ascii_case(prefix))
+        });
+        if !has_prefix && found.is_none() {
+            return None;
+        }
+    }
+    found
+}
+
+/// Calls the function with every suffix of every input, which is what the tokenizer does.
+fn for_each_suffix<F: FnMut(&str)>(inputs: &[String], mut f: F) {
+    for input in inputs {
+        for (index, _) in input.char_indices() {
+            f(&input[index..]);
+        }
+    }
+}
+
+fn bench_keywords(c: &mut Criterion) {
+    let inputs = inputs();
+    let keywords = KeywordSet::new();
+    let keys = keywords.iter().collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("longest_prefix");
+    group.bench_function("linear", |b| {
+        b.iter(|| {
+            for_each_suffix(&inputs, |s| {
+                black_box(linear_longest_prefix(&keys, black_box(s)));
+            })
+        })
+    });
+    group.bench_function("trie", |b| {
+        b.iter(|| {
+            for_each_suffix(&inputs, |s| {
+                black_box(keywords.longest_prefix(black_box(s)));
+            })
+        })
+    });
+    group.finish();
+
+    c.bench_function("parse_data_json", |b| {
+        let mut parser = anitomy::Parser::default();
+        let mut results = Vec::new();
+        b.iter(|| {
+            for input in &inputs {
+                parser.parse_into(black_box(input), &mut results);
+                black_box(&results);
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_keywords);
+criterion_main!(benches);
@@ -0,0 +1,158 @@
+/* SPDX-License-Identifier: MPL-2.0 */
+
+#ifndef ANITOMY_H
+#define ANITOMY_H
+
+/* This file is generated by cbindgen, do not edit it by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The kind of a parsed element.
+//
+// The values are stable, new kinds are only ever added at the end.
+typedef enum AnitomyElementKind {
+  ANITOMY_ELEMENT_KIND_AUDIO_TERM = 0,
+  ANITOMY_ELEMENT_KIND_DEVICE_COMPATIBILITY = 1,
+  ANITOMY_ELEMENT_KIND_EPISODE = 2,
+  ANITOMY_ELEMENT_KIND_EPISODE_TITLE = 3,
+  ANITOMY_ELEMENT_KIND_EPISODE_ALT = 4,
+  ANITOMY_ELEMENT_KIND_FILE_CHECKSUM = 5,
+  ANITOMY_ELEMENT_KIND_FILE_EXTENSION = 6,
+  ANITOMY_ELEMENT_KIND_LANGUAGE = 7,
+  ANITOMY_ELEMENT_KIND_OTHER = 8,
+  ANITOMY_ELEMENT_KIND_RELEASE_GROUP = 9,
+  ANITOMY_ELEMENT_KIND_RELEASE_INFORMATION = 10,
+  ANITOMY_ELEMENT_KIND_RELEASE_VERSION = 11,
+  ANITOMY_ELEMENT_KIND_SEASON = 12,
+  ANITOMY_ELEMENT_KIND_SOURCE = 13,
+  ANITOMY_ELEMENT_KIND_SUBTITLES = 14,
+  ANITOMY_ELEMENT_KIND_TITLE = 15,
+  ANITOMY_ELEMENT_KIND_TYPE = 16,
+  ANITOMY_ELEMENT_KIND_VIDEO_RESOLUTION = 17,
+  ANITOMY_ELEMENT_KIND_VIDEO_TERM = 18,
+  ANITOMY_ELEMENT_KIND_VOLUME = 19,
+  ANITOMY_ELEMENT_KIND_PART = 20,
+  ANITOMY_ELEMENT_KIND_YEAR = 21,
+  ANITOMY_ELEMENT_KIND_DATE = 22,
+  ANITOMY_ELEMENT_KIND_UNKNOWN = 23,
+  ANITOMY_ELEMENT_KIND_ALTERNATIVE_TITLE = 24,
+  ANITOMY_ELEMENT_KIND_EPISODE_TOTAL = 25,
+  ANITOMY_ELEMENT_KIND_VOLUME_TOTAL = 26,
+} AnitomyElementKind;
+
+// A toggle in [`AnitomyOptions`].
+typedef enum AnitomyOption {
+  ANITOMY_OPTION_EPISODE = 0,
+  ANITOMY_OPTION_EPISODE_TITLE = 1,
+  ANITOMY_OPTION_FILE_CHECKSUM = 2,
+  ANITOMY_OPTION_FILE_EXTENSION = 3,
+  ANITOMY_OPTION_RELEASE_GROUP = 4,
+  ANITOMY_OPTION_SEASON = 5,
+  ANITOMY_OPTION_TITLE = 6,
+  ANITOMY_OPTION_VIDEO_RESOLUTION = 7,
+  ANITOMY_OPTION_YEAR = 8,
+  ANITOMY_OPTION_DATE = 9,
+  ANITOMY_OPTION_UNKNOWN_TOKENS = 10,
+  ANITOMY_OPTION_SCENE = 11,
+  ANITOMY_OPTION_TORRENT = 12,
+} AnitomyOption;
+
+// The result of a parse, created with `anitomy_parse`.
+typedef struct AnitomyElements AnitomyElements;
+
+// The options used for parsing, created with `anitomy_options_new`.
+typedef struct AnitomyOptions AnitomyOptions;
+
+// A view into a single element of [`AnitomyElements`].
+//
+// The value is NUL terminated and is valid until the elements are freed.
+typedef struct AnitomyElement {
+  enum AnitomyElementKind kind;
+  const char *value;
+  // The length of the value in bytes, without the NUL terminator
+  size_t value_len;
+  // The start of the element in the input, in bytes
+  size_t span_start;
+  // The end of the element in the input, in bytes
+  size_t span_end;
+} AnitomyElement;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates options with the default settings.
+struct AnitomyOptions *anitomy_options_new(void);
+
+// Toggles a single option, where `option` is one of the values of `AnitomyOption`.
+//
+// Returns false if `options` is NULL or the option is unknown.
+//
+// # Safety
+//
+// `options` must be a pointer returned by `anitomy_options_new` or NULL.
+bool anitomy_options_set(struct AnitomyOptions *options, uint32_t option, bool enabled);
+
+// Frees options created by `anitomy_options_new`.
+//
+// # Safety
+//
+// `options` must be a pointer returned by `anitomy_options_new` or NULL,
+// and must not be used afterwards.
+void anitomy_options_free(struct AnitomyOptions *options);
+
+// Parses a UTF-8 string of `len` bytes into its elements.
+//
+// If `options` is NULL then the default options are used. Returns NULL if the
+// input is NULL or not valid UTF-8.
+//
+// # Safety
+//
+// `input` must point to at least `len` readable bytes, and `options` must be
+// a pointer returned by `anitomy_options_new` or NULL.
+struct AnitomyElements *anitomy_parse(const char *input,
+                                      size_t len,
+                                      const struct AnitomyOptions *options);
+
+// Returns the number of parsed elements.
+//
+// # Safety
+//
+// `elements` must be a pointer returned by `anitomy_parse` or NULL.
+size_t anitomy_elements_len(const struct AnitomyElements *elements);
+
+// Writes the element at `index` into `out`.
+//
+// Returns false if the index is out of bounds, in which case `out` is left untouched.
+//
+// # Safety
+//
+// `elements` must be a pointer returned by `anitomy_parse` or NULL, and `out` must
+// be a valid pointer to write an `AnitomyElement` into.
+bool anitomy_elements_get(const struct AnitomyElements *elements,
+                          size_t index,
+                          struct AnitomyElement *out);
+
+// Frees elements returned by `anitomy_parse`.
+//
+// # Safety
+//
+// `elements` must be a pointer returned by `anitomy_parse` or NULL, and neither it
+// nor the values of its elements may be used afterwards.
+void anitomy_elements_free(struct AnitomyElements *elements);
+
+// Returns a human readable, NUL terminated name of the element kind, e.g. `Release Group`,
+// where `kind` is one of the values of `AnitomyElementKind`.
+//
+// The returned string is static and must not be freed. Returns NULL if the kind is unknown.
+const char *anitomy_element_kind_name(uint32_t kind);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ANITOMY_H */
@@ -0,0 +1,320 @@
+use std::borrow::Cow;
+
+use crate::{
+    candidates::Candidate,
+    element::{Element, ElementKind},
+    parser::parse_tokens,
+    rule::{Confidence, Rule},
+    Parser,
+};
+
+/// How many interpretations of every name are considered when looking for a consensus
+const CANDIDATE_LIMIT: usize = 8;
+
+/// The result of parsing a name as part of a batch, see [`crate::parse_batch_with_options`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchEntry<'a> {
+    /// The name that was parsed
+    pub input: &'a str,
+    /// The elements of the name, sorted by their position
+    pub elements: Vec<Element<'a>>,
+    /// The kinds whose values differ from parsing the name on its own
+    pub corrections: Vec<ElementKind>,
+}
+
+impl BatchEntry<'_> {
+    /// Returns whether the other names of the batch changed the result of this one
+    pub fn is_corrected(&self) -> bool {
+        !self.corrections.is_empty()
+    }
+}
+
+/// Lowercases a value and collapses everything that isn't alphanumeric into single spaces
+fn normalize(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn values<'e>(elements: &'e [Element<'_>], kind: ElementKind) -> impl Iterator<Item = &'e str> {
+    elements
+        .iter()
+        .filter(move |e| e.kind == kind)
+        .map(|e| e.value.as_ref())
+}
+
+/// Returns the normalized value of a kind that most names agree on.
+///
+/// Every name votes once for each value that any of its candidates has, and the value needs
+/// the votes of at least two names and of more than half of them.
+fn consensus(candidates: &[Vec<Candidate<'_>>], kind: ElementKind) -> Option<String> {
+    let mut votes: Vec<(String, usize)> = Vec::new();
+    for name in candidates {
+        let mut seen: Vec<String> = Vec::new();
+        for candidate in name {
+            for value in values(&candidate.elements, kind).map(normalize) {
+                if !seen.contains(&value) {
+                    seen.push(value);
+                }
+            }
+        }
+        for value in seen {
+            match votes.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, count)) => *count += 1,
+                None => votes.push((value, 1)),
+            }
+        }
+    }
+    // `max_by_key` returns the last maximum, so reverse to prefer the first value
+    let (value, count) = votes.into_iter().rev().max_by_key(|(_, count)| *count)?;
+    (count >= 2 && count * 2 > candidates.len()).then_some(value)
+}
+
+/// What most names of the batch agree on
+struct Consensus {
+    title: Option<String>,
+    release_group: Option<String>,
+    year: Option<String>,
+    /// Whether the names have different years, which are then likely to be episodes
+    years_vary: bool,
+    has_episode: bool,
+}
+
+impl Consensus {
+    fn new(originals: &[Vec<Element<'_>>], candidates: &[Vec<Candidate<'_>>]) -> Self {
+        let mut consensus = Self {
+            title: consensus(candidates, ElementKind::Title),
+            release_group: consensus(candidates, ElementKind::ReleaseGroup),
+            year: consensus(candidates, ElementKind::Year),
+            years_vary: false,
+            has_episode: false,
+        };
+        let mut years: Vec<String> = Vec::new();
+        for elements in originals {
+            for year in values(elements, ElementKind::Year).map(normalize) {
+                if !years.contains(&year) {
+                    years.push(year);
+                }
+            }
+        }
+        consensus.years_vary = years.len() > 1;
+        let with_episode = candidates
+            .iter()
+            .filter(|name| {
+                name.iter().any(|c| {
+                    consensus.matches_title(&c.elements)
+                        && values(&c.elements, ElementKind::Episode).next().is_some()
+                })
+            })
+            .count();
+        consensus.has_episode = with_episode * 2 > candidates.len();
+        consensus
+    }
+
+    /// Returns whether the elements have the title of the batch, or if there is none
+    fn matches_title(&self, elements: &[Element<'_>]) -> bool {
+        match &self.title {
+            Some(title) => {
+                values(elements, ElementKind::Title).any(|value| normalize(value) == *title)
+            }
+            None => true,
+        }
+    }
+
+    /// Returns how well the elements agree with the rest of the batch
+    fn agreement(&self, elements: &[Element<'_>]) -> i32 {
+        let matches = |kind, expected: &Option<String>| {
+            expected.as_ref().is_some_and(|expected| {
+                values(elements, kind).any(|value| normalize(value) == *expected)
+            })
+        };
+        let mut agreement = 0;
+        if matches(ElementKind::Title, &self.title) {
+            agreement += 2;
+        }
+        if matches(ElementKind::ReleaseGroup, &self.release_group) {
+            agreement += 1;
+        }
+        if values(elements, ElementKind::Episode).next().is_some() == self.has_episode {
+            agreement += 1;
+        }
+        // A year that varies between the names is more likely to be an episode number
+        // unless it is unambiguous such as `(2019)`
+        let has_ambiguous_year = elements
+            .iter()
+            .any(|e| e.kind == ElementKind::Year && e.confidence() < Confidence::High);
+        if has_ambiguous_year && self.years_vary && !matches(ElementKind::Year, &self.year) {
+            agreement -= 1;
+        }
+        agreement
+    }
+
+    /// Splits a title such as `Group Title` into the release group and the title
+    fn split_release_group(&self, input: &str, elements: &mut Vec<Element<'_>>) {
+        let (Some(title), Some(release_group)) = (&self.title, &self.release_group) else {
+            return;
+        };
+        if values(elements, ElementKind::ReleaseGroup).next().is_some() {
+            return;
+        }
+        let Some(index) = elements.iter().position(|e| e.kind == ElementKind::Title) else {
+            return;
+        };
+
+        let element = &elements[index];
+        let words: Vec<&str> = element.value.split(' ').collect();
+        let Some(split) = (1..words.len()).find(|&split| {
+            normalize(&words[..split].join(" ")) == *release_group
+                && normalize(&words[split..].join(" ")) == *title
+        }) else {
+            return;
+        };
+
+        // Delimiters are replaced in the value, so find where the title starts in the input
+        let group_value = words[..split].join(" ");
+        let source = &input[element.span.clone()];
+        let rest = source.get(group_value.len()..).unwrap_or_default();
+        let title_start = element.span.start + source.len() - rest.len()
+            + (rest.len()
+                - rest
+                    .trim_start_matches(|c: char| !c.is_alphanumeric())
+                    .len());
+
+        let release_group = Element {
+            kind: ElementKind::ReleaseGroup,
+            value: Cow::Owned(group_value),
+            position: element.position,
+            span: element.span.start..(element.span.start + source.len() - rest.len()),
+            rule: Rule::BatchConsensus,
+        };
+        let title = Element {
+            kind: ElementKind::Title,
+            value: Cow::Owned(words[split..].join(" ")),
+            position: element.position,
+            span: title_start..element.span.end,
+            rule: Rule::BatchConsensus,
+        };
+        elements[index] = title;
+        elements.insert(index, release_group);
+    }
+}
+
+/// Returns whether the elements keep every element that was found by an unambiguous pattern
+fn keeps_unambiguous(original: &[Element<'_>], elements: &[Element<'_>]) -> bool {
+    original
+        .iter()
+        .filter(|e| e.confidence() == Confidence::High)
+        .all(|e| {
+            elements
+                .iter()
+                .any(|x| x.kind == e.kind && x.value == e.value)
+        })
+}
+
+/// Returns the kinds whose values differ between the two results, in order of appearance
+fn corrections(before: &[Element<'_>], after: &[Element<'_>]) -> Vec<ElementKind> {
+    let mut kinds: Vec<ElementKind> = Vec::new();
+    for kind in after.iter().chain(before).map(|e| e.kind) {
+        if !kinds.contains(&kind) && !values(before, kind).eq(values(after, kind)) {
+            kinds.push(kind);
+        }
+    }
+    kinds
+}
+
+/// Returns the candidate that agrees most with the batch, or the original result if every
+/// candidate loses an unambiguous element of it
+fn choose<'a>(
+    consensus: &Consensus,
+    original: &[Element<'a>],
+    candidates: Vec<Candidate<'a>>,
+) -> Vec<Element<'a>> {
+    // Only ambiguous decisions are corrected and the candidates are ranked, so the best ranked
+    // one wins ties in agreement
+    candidates
+        .into_iter()
+        .enumerate()
+        .filter(|(_, c)| keeps_unambiguous(original, &c.elements))
+        .max_by_key(|(rank, c)| (consensus.agreement(&c.elements), std::cmp::Reverse(*rank)))
+        .map(|(_, c)| c.elements)
+        // The original result may not be among the candidates
+        .unwrap_or_else(|| original.to_vec())
+}
+
+pub(crate) fn parse_batch<'a>(parser: &Parser, inputs: &[&'a str]) -> Vec<BatchEntry<'a>> {
+    // The best ranked candidate isn't necessarily the result of parsing the name on its own
+    let originals: Vec<Vec<Element<'a>>> = inputs
+        .iter()
+        .map(|input| {
+            let mut tokens = parser.tokenize(input);
+            let mut elements = Vec::new();
+            parse_tokens(&mut tokens, parser.options(), &mut elements, None);
+            elements
+        })
+        .collect();
+    let candidates: Vec<Vec<Candidate<'a>>> = inputs
+        .iter()
+        .map(|input| parser.candidates(input, CANDIDATE_LIMIT))
+        .collect();
+    let consensus = Consensus::new(&originals, &candidates);
+
+    inputs
+        .iter()
+        .zip(originals)
+        .zip(candidates)
+        .map(|((&input, original), mut candidates)| {
+            for candidate in &mut candidates {
+                consensus.split_release_group(input, &mut candidate.elements);
+            }
+            // A name of another series is left alone, it doesn't have to agree with the batch
+            if !candidates
+                .iter()
+                .any(|c| consensus.matches_title(&c.elements))
+            {
+                return BatchEntry {
+                    input,
+                    elements: original,
+                    corrections: Vec::new(),
+                };
+            }
+            let elements = choose(&consensus, &original, candidates);
+            BatchEntry {
+                input,
+                corrections: corrections(&original, &elements),
+                elements,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Show_Name!  2"), "show name 2");
+        assert_eq!(normalize("86"), "86");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn test_choose_keeps_original() {
+        let original = crate::parse("[Group] Title - S01E01 [1080p].mkv");
+        let elements = original
+            .iter()
+            .filter(|e| e.kind != ElementKind::Episode)
+            .cloned()
+            .collect();
+        let candidates = vec![Candidate {
+            elements,
+            score: 1.0,
+        }];
+        let consensus = Consensus::new(&[], &[]);
+        assert_eq!(choose(&consensus, &original, candidates), original);
+        assert_eq!(choose(&consensus, &original, Vec::new()), original);
+    }
+}
@@ -0,0 +1,521 @@
+use std::{
+    io::{self, BufRead, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anitomy::{
+    organize::{self, Action, Layout, Organizer},
+    Element, MultiElementObject, Options, Parser, Preset, Template,
+};
+
+const USAGE: &str = "\
+Usage: anitomy [OPTIONS] [NAME]...
+       anitomy parse [OPTIONS] [NAME]...
+       anitomy organize [OPTIONS] <SOURCE> <TARGET>
+       anitomy undo <JOURNAL>
+
+Parses anime filenames into their elements.
+
+If no names are given then they are read from stdin, one per line. Names that start with `-`
+or are the name of a command can be given after `--` or with the `parse` command.
+
+Commands:
+  parse     Parses the names, which is the default
+  organize  Organizes the files in SOURCE into the layout of a media server in TARGET
+  undo      Reverts the changes recorded in the journal of `organize`
+
+Options:
+  -f, --format <FORMAT>   The output format: table, json, jsonl or csv [default: table]
+  -d, --dir <PATH>        Parse the names of the entries in a directory
+  -h, --help              Print this help message
+  -V, --version           Print the version
+
+Parser options, each can be prefixed with `no-` to disable it:
+      --episode           Parse episodes [default: on]
+      --episode-title     Parse episode titles [default: on]
+      --file-checksum     Parse file checksums [default: on]
+      --file-extension    Parse file extensions [default: on]
+      --release-group     Parse release groups [default: on]
+      --season            Parse seasons [default: on]
+      --title             Parse titles [default: on]
+      --video-resolution  Parse video resolutions [default: on]
+      --year              Parse years [default: on]
+      --date              Parse dates [default: on]
+      --unknown-tokens    Report tokens that were not understood [default: off]
+      --scene             Parse every name as a scene release [default: detected]
+      --torrent           Parse names as torrent titles from indexer feeds [default: off]
+
+Organize options:
+  -p, --preset <PRESET>      The layout: plex, jellyfin or kodi [default: plex]
+  -t, --template <TEMPLATE>  A custom layout, where `/` separates directories
+  -a, --action <ACTION>      How files are organized: move, hardlink or symlink [default: move]
+  -n, --dry-run              Print the plan without changing anything
+  -j, --journal <PATH>       A new journal to record changes in [default: a new journal in TARGET]
+";
+
+/// The keys of an [`anitomy::ElementObject`], used as the CSV columns
+const COLUMNS: &[&str] = &[
+    "audio_term",
+    "device_compatibility",
+    "episode",
+    "episode_alt",
+    "episode_title",
+    "file_checksum",
+    "file_extension",
+    "language",
+    "other",
+    "release_group",
+    "release_information",
+    "release_version",
+    "season",
+    "source",
+    "subtitles",
+    "title",
+    "type",
+    "video_resolution",
+    "video_term",
+    "volume",
+    "part",
+    "year",
+    "date",
+    "unknown",
+    "alternative_title",
+    "episode_total",
+    "volume_total",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Table,
+    Json,
+    JsonLines,
+    Csv,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "table" => Some(Self::Table),
+            "json" => Some(Self::Json),
+            "jsonl" => Some(Self::JsonLines),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Args {
+    format: Format,
+    options: Options,
+    dirs: Vec<PathBuf>,
+    names: Vec<String>,
+}
+
+#[derive(Debug)]
+struct OrganizeArgs {
+    layout: Layout,
+    action: Action,
+    options: Options,
+    dry_run: bool,
+    journal: Option<PathBuf>,
+    source: PathBuf,
+    target: PathBuf,
+}
+
+#[derive(Debug)]
+enum Command {
+    Parse(Args),
+    Organize(OrganizeArgs),
+    Undo(PathBuf),
+}
+
+#[derive(serde::Serialize)]
+struct Output<'a> {
+    input: &'a str,
+    #[serde(flatten)]
+    elements: MultiElementObject<'a>,
+}
+
+fn toggle_option(options: Options, name: &str, toggle: bool) -> Option<Options> {
+    let options = match name {
+        "episode" => options.episodes(toggle),
+        "episode-title" => options.episode_titles(toggle),
+        "file-checksum" => options.file_checksums(toggle),
+        "file-extension" => options.file_extensions(toggle),
+        "release-group" => options.release_groups(toggle),
+        "season" => options.seasons(toggle),
+        "title" => options.titles(toggle),
+        "video-resolution" => options.video_resolutions(toggle),
+        "year" => options.years(toggle),
+        "date" => options.dates(toggle),
+        "unknown-tokens" => options.unknown_tokens(toggle),
+        "scene" => options.scene(toggle),
+        "torrent" => options.torrent(toggle),
+        _ => return None,
+    };
+    Some(options)
+}
+
+/// Splits `--flag=value` into the flag and its inline value
+fn split_flag(arg: &str) -> (&str, Option<String>) {
+    match arg.split_once('=') {
+        Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+        _ => (arg, None),
+    }
+}
+
+/// Returns an error if a flag that doesn't take a value was given one, e.g. `--episode=false`
+fn reject_value(flag: &str, inline: &Option<String>) -> Result<(), String> {
+    match inline {
+        Some(value) => Err(format!("`{flag}` doesn't take a value, got `{value}`")),
+        None => Ok(()),
+    }
+}
+
+/// Toggles the parser option of a `--option` or `--no-option` flag
+fn toggle_flag(options: Options, flag: &str) -> Result<Options, String> {
+    let name = &flag[2..];
+    let options = match name.strip_prefix("no-") {
+        Some(name) => toggle_option(options, name, false),
+        None => toggle_option(options, name, true),
+    };
+    options.ok_or_else(|| format!("unknown option `{flag}`"))
+}
+
+fn parse_args(iter: impl Iterator<Item = String>) -> Result<Option<Command>, String> {
+    let mut iter = iter.peekable();
+    match iter.peek().map(String::as_str) {
+        Some("parse") => {
+            iter.next();
+            parse_names_args(iter).map(|args| args.map(Command::Parse))
+        }
+        Some("organize") => {
+            iter.next();
+            parse_organize_args(iter).map(|args| args.map(Command::Organize))
+        }
+        Some("undo") => {
+            iter.next();
+            match (iter.next(), iter.next()) {
+                (Some(journal), None) if !journal.starts_with('-') => {
+                    Ok(Some(Command::Undo(journal.into())))
+                }
+                _ => Err("`undo` takes the path of a journal".to_owned()),
+            }
+        }
+        _ => parse_names_args(iter).map(|args| args.map(Command::Parse)),
+    }
+}
+
+fn parse_organize_args(
+    mut iter: impl Iterator<Item = String>,
+) -> Result<Option<OrganizeArgs>, String> {
+    let mut layout = Layout::Preset(Preset::Plex);
+    let mut action = Action::Move;
+    let mut options = Options::default();
+    let mut dry_run = false;
+    let mut journal = None;
+    let mut paths = Vec::new();
+
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = split_flag(&arg);
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| iter.next())
+                .ok_or_else(|| format!("missing value for `{name}`"))
+        };
+
+        let takes_value = matches!(
+            flag,
+            "-p" | "--preset" | "-t" | "--template" | "-a" | "--action" | "-j" | "--journal"
+        );
+        if !takes_value {
+            reject_value(flag, &inline)?;
+        }
+
+        match flag {
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return Ok(None);
+            }
+            "-p" | "--preset" => {
+                let name = value(flag)?;
+                let preset =
+                    Preset::from_name(&name).ok_or_else(|| format!("unknown preset `{name}`"))?;
+                layout = Layout::Preset(preset);
+            }
+            "-t" | "--template" => {
+                let template = value(flag)?;
+                let template = Template::new(&template).map_err(|e| format!("{flag}: {e}"))?;
+                layout = Layout::Template(template);
+            }
+            "-a" | "--action" => {
+                let name = value(flag)?;
+                action =
+                    Action::from_name(&name).ok_or_else(|| format!("unknown action `{name}`"))?;
+            }
+            "-n" | "--dry-run" => dry_run = true,
+            "-j" | "--journal" => journal = Some(value(flag)?.into()),
+            "--" => {
+                paths.extend(iter.by_ref());
+                break;
+            }
+            _ if flag.starts_with("--") => options = toggle_flag(options, flag)?,
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option `{flag}`"));
+            }
+            _ => paths.push(arg),
+        }
+    }
+
+    let [source, target] = <[String; 2]>::try_from(paths)
+        .map_err(|_| "`organize` takes a source and a target directory".to_owned())?;
+    Ok(Some(OrganizeArgs {
+        layout,
+        action,
+        options,
+        dry_run,
+        journal,
+        source: source.into(),
+        target: target.into(),
+    }))
+}
+
+fn parse_names_args(mut iter: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut args = Args {
+        format: Format::Table,
+        options: Options::default(),
+        dirs: Vec::new(),
+        names: Vec::new(),
+    };
+
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = split_flag(&arg);
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| iter.next())
+                .ok_or_else(|| format!("missing value for `{name}`"))
+        };
+
+        if !matches!(flag, "-f" | "--format" | "-d" | "--dir") {
+            reject_value(flag, &inline)?;
+        }
+
+        match flag {
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return Ok(None);
+            }
+            "-V" | "--version" => {
+                println!("anitomy {}", env!("CARGO_PKG_VERSION"));
+                return Ok(None);
+            }
+            "-f" | "--format" => {
+                let name = value(flag)?;
+                args.format =
+                    Format::from_name(&name).ok_or_else(|| format!("unknown format `{name}`"))?;
+            }
+            "-d" | "--dir" => args.dirs.push(value(flag)?.into()),
+            "--" => {
+                args.names.extend(iter);
+                break;
+            }
+            _ if flag.starts_with("--") => args.options = toggle_flag(args.options, flag)?,
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option `{flag}`"));
+            }
+            _ => args.names.push(arg),
+        }
+    }
+
+    Ok(Some(args))
+}
+
+fn read_names(args: &mut Args) -> io::Result<Vec<String>> {
+    let mut names = std::mem::take(&mut args.names);
+    for dir in &args.dirs {
+        let mut entries = std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        names.extend(entries);
+    }
+
+    if names.is_empty() && args.dirs.is_empty() {
+        for line in io::stdin().lock().lines() {
+            let line = line?;
+            let line = line.trim_end_matches('\r');
+            if !line.is_empty() {
+                names.push(line.to_owned());
+            }
+        }
+    }
+    Ok(names)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn write_table(out: &mut impl Write, input: &str, elements: &[Element<'_>]) -> io::Result<()> {
+    writeln!(out, "{input}")?;
+    let width = elements
+        .iter()
+        .map(|e| e.kind().as_str().len())
+        .max()
+        .unwrap_or_default();
+    for element in elements {
+        writeln!(
+            out,
+            "  {:<width$}  {}",
+            element.kind().as_str(),
+            element.value()
+        )?;
+    }
+    writeln!(out)
+}
+
+/// Writes a row of the CSV output, where a kind found multiple times has its values joined by `;`
+fn write_csv_row(out: &mut impl Write, input: &str, elements: &[Element<'_>]) -> io::Result<()> {
+    write!(out, "{}", csv_field(input))?;
+    for column in COLUMNS {
+        let values: Vec<_> = elements
+            .iter()
+            .filter(|e| e.kind().as_key() == *column)
+            .map(|e| e.value())
+            .collect();
+        write!(out, ",{}", csv_field(&values.join(";")))?;
+    }
+    writeln!(out)
+}
+
+fn run(args: &mut Args) -> io::Result<()> {
+    let names = read_names(args)?;
+    let mut parser = Parser::new(args.options);
+    let mut elements = Vec::new();
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    match args.format {
+        Format::Json => write!(out, "[")?,
+        Format::Csv => writeln!(out, "input,{}", COLUMNS.join(","))?,
+        Format::Table | Format::JsonLines => {}
+    }
+
+    for (index, name) in names.iter().enumerate() {
+        parser.parse_into(name, &mut elements);
+        match args.format {
+            Format::Table => write_table(&mut out, name, &elements)?,
+            Format::Json | Format::JsonLines => {
+                let output = Output {
+                    input: name,
+                    elements: elements.iter().collect(),
+                };
+                if args.format == Format::Json && index > 0 {
+                    write!(out, ",")?;
+                }
+                serde_json::to_writer(&mut out, &output)?;
+                if args.format == Format::JsonLines {
+                    writeln!(out)?;
+                }
+            }
+            Format::Csv => write_csv_row(&mut out, name, &elements)?,
+        }
+    }
+
+    if args.format == Format::Json {
+        writeln!(out, "]")?;
+    }
+    out.flush()
+}
+
+/// Returns the path of a journal in the target that doesn't exist yet, so every run gets its own
+/// journal and undoing only reverts that run
+fn new_journal_path(target: &Path) -> PathBuf {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    (0..)
+        .map(|n| match n {
+            0 => target.join(format!(".anitomy-journal-{time}.tsv")),
+            n => target.join(format!(".anitomy-journal-{time}-{n}.tsv")),
+        })
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+/// Plans and applies the organization, returns false if there were conflicts
+fn run_organize(args: &OrganizeArgs) -> io::Result<bool> {
+    let plan = Organizer::new(args.layout.clone())
+        .action(args.action)
+        .options(args.options)
+        .plan(&args.source, &args.target)?;
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    write!(out, "{plan}")?;
+    out.flush()?;
+    if !args.dry_run && plan.files().next().is_some() {
+        let journal = match &args.journal {
+            Some(journal) => journal.clone(),
+            None => {
+                std::fs::create_dir_all(&args.target)?;
+                new_journal_path(&args.target)
+            }
+        };
+        let count = plan.apply(&journal)?;
+        eprintln!(
+            "anitomy: organized {count} files, undo with `anitomy undo {}`",
+            journal.display()
+        );
+    }
+    Ok(plan.conflicts.is_empty())
+}
+
+fn main() -> ExitCode {
+    let command = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(command)) => command,
+        Ok(None) => return ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("anitomy: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = match command {
+        Command::Parse(mut args) => run(&mut args),
+        Command::Organize(args) => match run_organize(&args) {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                eprintln!("anitomy: conflicting files were left alone");
+                return ExitCode::FAILURE;
+            }
+            Err(e) => Err(e),
+        },
+        Command::Undo(journal) => organize::undo(&journal).map(|undone| {
+            for target in &undone.skipped {
+                eprintln!(
+                    "anitomy: kept {}, it no longer links to the original file",
+                    target.display()
+                );
+            }
+            eprintln!("anitomy: reverted {} files", undone.reverted);
+        }),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // Piping into e.g. `head` closes stdout early, which isn't an error
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("anitomy: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::VecDeque;
+
+use crate::{
+    element::{Element, ElementKind},
+    parser::{parse_tokens, year::is_year},
+    rule::{Confidence, Rule},
+    tokenizer::{Token, TokenKind},
+    Options,
+};
+
+/// How much a candidate is penalised for every decision that differs from the default parse
+const DEVIATION_PENALTY: f32 = 0.1;
+
+/// The maximum number of decisions that are changed at once
+const MAX_DEVIATIONS: usize = 2;
+
+/// A single interpretation of the input, see [`crate::parse_candidates_with_options`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate<'a> {
+    /// The elements of this interpretation, sorted by their position
+    pub elements: Vec<Element<'a>>,
+    /// The score of this interpretation, where higher is better
+    pub score: f32,
+}
+
+/// The ambiguous decisions that were flipped from the default parse.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Variant {
+    /// Tokens that are treated as plain text, e.g. a number that shouldn't be an episode
+    text: Vec<usize>,
+    /// Ambiguous keyword tokens that are treated as unambiguous
+    strict: Vec<usize>,
+    /// A number in the title that is treated as the episode, e.g. `100` in `Mob Psycho 100`
+    episode: Option<usize>,
+    /// Numbers that are treated as years instead of episodes or part of the title
+    years: Vec<usize>,
+    no_years: bool,
+}
+
+impl Variant {
+    fn deviations(&self) -> usize {
+        self.text.len()
+            + self.strict.len()
+            + usize::from(self.episode.is_some())
+            + self.years.len()
+            + usize::from(self.no_years)
+    }
+
+    fn apply<'a>(
+        &self,
+        tokens: &mut [Token<'a>],
+        options: Options,
+        results: &mut Vec<Element<'a>>,
+    ) -> Options {
+        for &index in &self.text {
+            tokens[index].kind = TokenKind::Text;
+            tokens[index].keyword = None;
+        }
+        for &index in &self.strict {
+            tokens[index].keyword = tokens[index].keyword.map(|k| k.with_ambiguous(false));
+        }
+        if let Some(index) = self.episode {
+            tokens[index].mark_known();
+            results.push(Element::new(
+                ElementKind::Episode,
+                Rule::LastNumber,
+                &tokens[index],
+            ));
+        }
+        for &index in &self.years {
+            tokens[index].mark_known();
+            results.push(Element::new(
+                ElementKind::Year,
+                Rule::IsolatedYear,
+                &tokens[index],
+            ));
+        }
+        options.years(options.parse_year() && !self.no_years)
+    }
+
+    /// Returns the variants that flip one more decision made in the given parse
+    fn branches(&self, tokens: &[Token<'_>], elements: &[Element<'_>]) -> Vec<Variant> {
+        let mut branches = Vec::new();
+        if self.deviations() >= MAX_DEVIATIONS {
+            return branches;
+        }
+
+        // A number that was picked as the episode might be part of the title instead
+        for element in elements.iter().filter(|e| e.kind == ElementKind::Episode) {
+            let index = element.position;
+            if tokens[index].is_number() && !self.text.contains(&index) {
+                let mut variant = self.clone();
+                variant.text.push(index);
+                variant.text.sort_unstable();
+                branches.push(variant);
+            }
+        }
+
+        // A number at the end of the title might be the episode instead, in which case the
+        // number that was picked as the episode is something else
+        let title = elements.iter().find(|e| e.kind == ElementKind::Title);
+        if let Some(title) = title.filter(|_| self.episode.is_none()) {
+            let episodes: Vec<_> = elements
+                .iter()
+                .filter(|e| e.kind == ElementKind::Episode && tokens[e.position].is_number())
+                .map(|e| e.position)
+                .collect();
+            for index in title_numbers(tokens, title) {
+                if is_year(tokens[index].value) {
+                    continue;
+                }
+                let mut variant = self.clone();
+                variant.episode = Some(index);
+                for &episode in &episodes {
+                    if !variant.text.contains(&episode) {
+                        variant.text.push(episode);
+                    }
+                }
+                variant.text.sort_unstable();
+                branches.push(variant);
+            }
+        }
+
+        // A number that looks like a year might be one instead of an episode or part of the title
+        let episodes = elements
+            .iter()
+            .filter(|e| e.kind == ElementKind::Episode)
+            .map(|e| e.position);
+        let title_numbers = title.into_iter().flat_map(|e| title_numbers(tokens, e));
+        for index in episodes.chain(title_numbers) {
+            if tokens[index].is_number()
+                && is_year(tokens[index].value)
+                && !self.years.contains(&index)
+            {
+                let mut variant = self.clone();
+                variant.years.push(index);
+                variant.years.sort_unstable();
+                branches.push(variant);
+            }
+        }
+
+        // A year might be an episode or part of the title instead
+        if !self.no_years && elements.iter().any(|e| e.kind == ElementKind::Year) {
+            branches.push(Variant {
+                no_years: true,
+                ..self.clone()
+            });
+        }
+
+        // An ambiguous keyword might be either just a keyword or just part of the title
+        for (index, token) in tokens.iter().enumerate() {
+            let is_ambiguous =
+                !token.is_enclosed && token.keyword.is_some_and(|k| k.is_ambiguous());
+            if !is_ambiguous || self.text.contains(&index) || self.strict.contains(&index) {
+                continue;
+            }
+            let mut variant = self.clone();
+            variant.text.push(index);
+            variant.text.sort_unstable();
+            branches.push(variant);
+            let mut variant = self.clone();
+            variant.strict.push(index);
+            variant.strict.sort_unstable();
+            branches.push(variant);
+        }
+
+        branches
+    }
+}
+
+/// Returns the indices of the number tokens within the title, except for its first token
+fn title_numbers<'t>(
+    tokens: &'t [Token<'_>],
+    title: &Element<'_>,
+) -> impl Iterator<Item = usize> + 't {
+    let span = title.span.clone();
+    let first = title.position;
+    tokens
+        .iter()
+        .enumerate()
+        .filter(move |(_, t)| span.start <= t.span.start && t.span.end <= span.end)
+        .filter(move |&(index, t)| index != first && t.is_number())
+        .map(|(index, _)| index)
+}
+
+fn weight(confidence: Confidence) -> f32 {
+    match confidence {
+        Confidence::Low => 0.3,
+        Confidence::Medium => 0.6,
+        Confidence::High => 1.0,
+    }
+}
+
+/// Scores an interpretation by how much of the input it explains and how confidently.
+///
+/// Every character outside of delimiters and brackets counts with the weight of the most confident element
+/// that covers it, so parts of the input that are left unclaimed lower the score.
+fn score(tokens: &[Token<'_>], elements: &[Element<'_>], deviations: usize) -> f32 {
+    let mut covered = 0.0;
+    let mut total = 0;
+    let is_content = |t: &&Token<'_>| {
+        matches!(
+            t.kind,
+            TokenKind::Keyword | TokenKind::Text | TokenKind::Number
+        )
+    };
+    for token in tokens.iter().filter(is_content) {
+        let len = token.span.len();
+        total += len;
+        covered += len as f32
+            * elements
+                .iter()
+                .filter(|e| e.span.start < token.span.end && token.span.start < e.span.end)
+                .map(|e| weight(e.confidence()))
+                .fold(0.0, f32::max);
+    }
+    let confidence = if total == 0 {
+        0.0
+    } else {
+        covered / total as f32
+    };
+    confidence - deviations as f32 * DEVIATION_PENALTY
+}
+
+fn is_same_interpretation(a: &[Element<'_>], b: &[Element<'_>]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(x, y)| x.kind == y.kind && x.value == y.value)
+}
+
+/// Parses the tokens multiple times while flipping ambiguous decisions.
+///
+/// Returns at most `limit` distinct candidates, sorted from best to worst.
+pub(crate) fn parse_candidates<'a>(
+    tokens: &[Token<'a>],
+    options: Options,
+    limit: usize,
+) -> Vec<Candidate<'a>> {
+    // Every interpretation is a full parse, so bound the search to keep it predictable
+    let max_parses = limit.saturating_mul(4).max(8);
+    let mut candidates: Vec<Candidate<'a>> = Vec::new();
+    let mut seen = Vec::new();
+    let mut queue = VecDeque::from([Variant::default()]);
+    let mut buffer = Vec::with_capacity(tokens.len());
+    let mut parses = 0;
+
+    while let Some(variant) = queue.pop_front() {
+        if limit == 0 || parses >= max_parses {
+            break;
+        }
+        parses += 1;
+
+        buffer.clear();
+        buffer.extend_from_slice(tokens);
+        let mut elements = Vec::new();
+        let options = variant.apply(&mut buffer, options, &mut elements);
+        parse_tokens(&mut buffer, options, &mut elements, None);
+
+        for branch in variant.branches(&buffer, &elements) {
+            if !seen.contains(&branch) {
+                seen.push(branch.clone());
+                queue.push_back(branch);
+            }
+        }
+
+        if candidates
+            .iter()
+            .any(|c| is_same_interpretation(&c.elements, &elements))
+        {
+            continue;
+        }
+        let score = score(tokens, &elements, variant.deviations());
+        candidates.push(Candidate { elements, score });
+    }
+
+    // The sort is stable, so the default parse wins ties
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.truncate(limit);
+    candidates
+}
@@ -0,0 +1,370 @@
+//! A C API over the parser.
+//!
+//! Every handle that is returned by a function has to be freed with its respective `_free`
+//! function. Strings are passed as UTF-8 encoded bytes along with their length in bytes,
+//! and the byte spans of elements are relative to the input string.
+//!
+//! The header for this API lives in `include/anitomy.h`.
+
+use std::{ffi::c_char, ops::Range, ptr, slice};
+
+use crate::{ElementKind, Options, Parser};
+
+/// The kind of a parsed element.
+///
+/// The values are stable, new kinds are only ever added at the end.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnitomyElementKind {
+    AudioTerm = 0,
+    DeviceCompatibility = 1,
+    Episode = 2,
+    EpisodeTitle = 3,
+    EpisodeAlt = 4,
+    FileChecksum = 5,
+    FileExtension = 6,
+    Language = 7,
+    Other = 8,
+    ReleaseGroup = 9,
+    ReleaseInformation = 10,
+    ReleaseVersion = 11,
+    Season = 12,
+    Source = 13,
+    Subtitles = 14,
+    Title = 15,
+    Type = 16,
+    VideoResolution = 17,
+    VideoTerm = 18,
+    Volume = 19,
+    Part = 20,
+    Year = 21,
+    Date = 22,
+    Unknown = 23,
+    AlternativeTitle = 24,
+    EpisodeTotal = 25,
+    VolumeTotal = 26,
+}
+
+impl From<ElementKind> for AnitomyElementKind {
+    fn from(kind: ElementKind) -> Self {
+        match kind {
+            ElementKind::AudioTerm => Self::AudioTerm,
+            ElementKind::DeviceCompatibility => Self::DeviceCompatibility,
+            ElementKind::Episode => Self::Episode,
+            ElementKind::EpisodeTitle => Self::EpisodeTitle,
+            ElementKind::EpisodeAlt => Self::EpisodeAlt,
+            ElementKind::FileChecksum => Self::FileChecksum,
+            ElementKind::FileExtension => Self::FileExtension,
+            ElementKind::Language => Self::Language,
+            ElementKind::Other => Self::Other,
+            ElementKind::ReleaseGroup => Self::ReleaseGroup,
+            ElementKind::ReleaseInformation => Self::ReleaseInformation,
+            ElementKind::ReleaseVersion => Self::ReleaseVersion,
+            ElementKind::Season => Self::Season,
+            ElementKind::Source => Self::Source,
+            ElementKind::Subtitles => Self::Subtitles,
+            ElementKind::Title => Self::Title,
+            ElementKind::Type => Self::Type,
+            ElementKind::VideoResolution => Self::VideoResolution,
+            ElementKind::VideoTerm => Self::VideoTerm,
+            ElementKind::Volume => Self::Volume,
+            ElementKind::Part => Self::Part,
+            ElementKind::Year => Self::Year,
+            ElementKind::Date => Self::Date,
+            ElementKind::Unknown => Self::Unknown,
+            ElementKind::AlternativeTitle => Self::AlternativeTitle,
+            ElementKind::EpisodeTotal => Self::EpisodeTotal,
+            ElementKind::VolumeTotal => Self::VolumeTotal,
+        }
+    }
+}
+
+impl TryFrom<u32> for AnitomyElementKind {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::AudioTerm),
+            1 => Ok(Self::DeviceCompatibility),
+            2 => Ok(Self::Episode),
+            3 => Ok(Self::EpisodeTitle),
+            4 => Ok(Self::EpisodeAlt),
+            5 => Ok(Self::FileChecksum),
+            6 => Ok(Self::FileExtension),
+            7 => Ok(Self::Language),
+            8 => Ok(Self::Other),
+            9 => Ok(Self::ReleaseGroup),
+            10 => Ok(Self::ReleaseInformation),
+            11 => Ok(Self::ReleaseVersion),
+            12 => Ok(Self::Season),
+            13 => Ok(Self::Source),
+            14 => Ok(Self::Subtitles),
+            15 => Ok(Self::Title),
+            16 => Ok(Self::Type),
+            17 => Ok(Self::VideoResolution),
+            18 => Ok(Self::VideoTerm),
+            19 => Ok(Self::Volume),
+            20 => Ok(Self::Part),
+            21 => Ok(Self::Year),
+            22 => Ok(Self::Date),
+            23 => Ok(Self::Unknown),
+            24 => Ok(Self::AlternativeTitle),
+            25 => Ok(Self::EpisodeTotal),
+            26 => Ok(Self::VolumeTotal),
+            _ => Err(value),
+        }
+    }
+}
+
+/// A toggle in [`AnitomyOptions`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnitomyOption {
+    Episode = 0,
+    EpisodeTitle = 1,
+    FileChecksum = 2,
+    FileExtension = 3,
+    ReleaseGroup = 4,
+    Season = 5,
+    Title = 6,
+    VideoResolution = 7,
+    Year = 8,
+    Date = 9,
+    UnknownTokens = 10,
+    Scene = 11,
+    Torrent = 12,
+}
+
+impl TryFrom<u32> for AnitomyOption {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Episode),
+            1 => Ok(Self::EpisodeTitle),
+            2 => Ok(Self::FileChecksum),
+            3 => Ok(Self::FileExtension),
+            4 => Ok(Self::ReleaseGroup),
+            5 => Ok(Self::Season),
+            6 => Ok(Self::Title),
+            7 => Ok(Self::VideoResolution),
+            8 => Ok(Self::Year),
+            9 => Ok(Self::Date),
+            10 => Ok(Self::UnknownTokens),
+            11 => Ok(Self::Scene),
+            12 => Ok(Self::Torrent),
+            _ => Err(value),
+        }
+    }
+}
+
+/// The options used for parsing, created with `anitomy_options_new`.
+pub struct AnitomyOptions(Options);
+
+/// The result of a parse, created with `anitomy_parse`.
+pub struct AnitomyElements {
+    /// The values are stored with a trailing NUL byte so they can be used as C strings
+    elements: Vec<(ElementKind, Vec<u8>, Range<usize>)>,
+}
+
+/// A view into a single element of [`AnitomyElements`].
+///
+/// The value is NUL terminated and is valid until the elements are freed.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct AnitomyElement {
+    pub kind: AnitomyElementKind,
+    pub value: *const c_char,
+    /// The length of the value in bytes, without the NUL terminator
+    pub value_len: usize,
+    /// The start of the element in the input, in bytes
+    pub span_start: usize,
+    /// The end of the element in the input, in bytes
+    pub span_end: usize,
+}
+
+/// Creates options with the default settings.
+#[no_mangle]
+pub extern "C" fn anitomy_options_new() -> *mut AnitomyOptions {
+    Box::into_raw(Box::new(AnitomyOptions(Options::default())))
+}
+
+/// Toggles a single option, where `option` is one of the values of `AnitomyOption`.
+///
+/// Returns false if `options` is NULL or the option is unknown.
+///
+/// # Safety
+///
+/// `options` must be a pointer returned by `anitomy_options_new` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn anitomy_options_set(
+    options: *mut AnitomyOptions,
+    option: u32,
+    enabled: bool,
+) -> bool {
+    let Some(AnitomyOptions(options)) = options.as_mut() else {
+        return false;
+    };
+    let Ok(option) = AnitomyOption::try_from(option) else {
+        return false;
+    };
+    *options = match option {
+        AnitomyOption::Episode => options.episodes(enabled),
+        AnitomyOption::EpisodeTitle => options.episode_titles(enabled),
+        AnitomyOption::FileChecksum => options.file_checksums(enabled),
+        AnitomyOption::FileExtension => options.file_extensions(enabled),
+        AnitomyOption::ReleaseGroup => options.release_groups(enabled),
+        AnitomyOption::Season => options.seasons(enabled),
+        AnitomyOption::Title => options.titles(enabled),
+        AnitomyOption::VideoResolution => options.video_resolutions(enabled),
+        AnitomyOption::Year => options.years(enabled),
+        AnitomyOption::Date => options.dates(enabled),
+        AnitomyOption::UnknownTokens => options.unknown_tokens(enabled),
+        AnitomyOption::Scene => options.scene(enabled),
+        AnitomyOption::Torrent => options.torrent(enabled),
+    };
+    true
+}
+
+/// Frees options created by `anitomy_options_new`.
+///
+/// # Safety
+///
+/// `options` must be a pointer returned by `anitomy_options_new` or NULL,
+/// and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn anitomy_options_free(options: *mut AnitomyOptions) {
+    if !options.is_null() {
+        drop(Box::from_raw(options));
+    }
+}
+
+/// Parses a UTF-8 string of `len` bytes into its elements.
+///
+/// If `options` is NULL then the default options are used. Returns NULL if the
+/// input is NULL or not valid UTF-8.
+///
+/// # Safety
+///
+/// `input` must point to at least `len` readable bytes, and `options` must be
+/// a pointer returned by `anitomy_options_new` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn anitomy_parse(
+    input: *const c_char,
+    len: usize,
+    options: *const AnitomyOptions,
+) -> *mut AnitomyElements {
+    if input.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(input) = std::str::from_utf8(slice::from_raw_parts(input.cast::<u8>(), len)) else {
+        return ptr::null_mut();
+    };
+    let options = options.as_ref().map(|o| o.0).unwrap_or_default();
+    let elements = Parser::new(options)
+        .parse(input)
+        .into_iter()
+        .map(|e| {
+            let mut value = Vec::with_capacity(e.value.len() + 1);
+            value.extend_from_slice(e.value.as_bytes());
+            value.push(0);
+            (e.kind, value, e.span)
+        })
+        .collect();
+    Box::into_raw(Box::new(AnitomyElements { elements }))
+}
+
+/// Returns the number of parsed elements.
+///
+/// # Safety
+///
+/// `elements` must be a pointer returned by `anitomy_parse` or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn anitomy_elements_len(elements: *const AnitomyElements) -> usize {
+    elements.as_ref().map_or(0, |e| e.elements.len())
+}
+
+/// Writes the element at `index` into `out`.
+///
+/// Returns false if the index is out of bounds, in which case `out` is left untouched.
+///
+/// # Safety
+///
+/// `elements` must be a pointer returned by `anitomy_parse` or NULL, and `out` must
+/// be a valid pointer to write an `AnitomyElement` into.
+#[no_mangle]
+pub unsafe extern "C" fn anitomy_elements_get(
+    elements: *const AnitomyElements,
+    index: usize,
+    out: *mut AnitomyElement,
+) -> bool {
+    let Some((kind, value, span)) = elements.as_ref().and_then(|e| e.elements.get(index)) else {
+        return false;
+    };
+    if out.is_null() {
+        return false;
+    }
+    out.write(AnitomyElement {
+        kind: (*kind).into(),
+        value: value.as_ptr().cast(),
+        value_len: value.len() - 1,
+        span_start: span.start,
+        span_end: span.end,
+    });
+    true
+}
+
+/// Frees elements returned by `anitomy_parse`.
+///
+/// # Safety
+///
+/// `elements` must be a pointer returned by `anitomy_parse` or NULL, and neither it
+/// nor the values of its elements may be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn anitomy_elements_free(elements: *mut AnitomyElements) {
+    if !elements.is_null() {
+        drop(Box::from_raw(elements));
+    }
+}
+
+/// Returns a human readable, NUL terminated name of the element kind, e.g. `Release Group`,
+/// where `kind` is one of the values of `AnitomyElementKind`.
+///
+/// The returned string is static and must not be freed. Returns NULL if the kind is unknown.
+#[no_mangle]
+pub extern "C" fn anitomy_element_kind_name(kind: u32) -> *const c_char {
+    let Ok(kind) = AnitomyElementKind::try_from(kind) else {
+        return ptr::null();
+    };
+    // Every name ends with a NUL byte, since C string literals need Rust 1.77
+    let name: &'static [u8] = match kind {
+        AnitomyElementKind::AudioTerm => b"Audio Term\0",
+        AnitomyElementKind::DeviceCompatibility => b"Device Compatibility\0",
+        AnitomyElementKind::Episode => b"Episode\0",
+        AnitomyElementKind::EpisodeTitle => b"Episode Title\0",
+        AnitomyElementKind::EpisodeAlt => b"Episode Alt\0",
+        AnitomyElementKind::FileChecksum => b"File Checksum\0",
+        AnitomyElementKind::FileExtension => b"File Extension\0",
+        AnitomyElementKind::Language => b"Language\0",
+        AnitomyElementKind::Other => b"Other\0",
+        AnitomyElementKind::ReleaseGroup => b"Release Group\0",
+        AnitomyElementKind::ReleaseInformation => b"Release Information\0",
+        AnitomyElementKind::ReleaseVersion => b"Release Version\0",
+        AnitomyElementKind::Season => b"Season\0",
+        AnitomyElementKind::Source => b"Source\0",
+        AnitomyElementKind::Subtitles => b"Subtitles\0",
+        AnitomyElementKind::Title => b"Title\0",
+        AnitomyElementKind::Type => b"Type\0",
+        AnitomyElementKind::VideoResolution => b"Video Resolution\0",
+        AnitomyElementKind::VideoTerm => b"Video Term\0",
+        AnitomyElementKind::Volume => b"Volume\0",
+        AnitomyElementKind::Part => b"Part\0",
+        AnitomyElementKind::Year => b"Year\0",
+        AnitomyElementKind::Date => b"Date\0",
+        AnitomyElementKind::Unknown => b"Unknown\0",
+        AnitomyElementKind::AlternativeTitle => b"Alternative Title\0",
+        AnitomyElementKind::EpisodeTotal => b"Episode Total\0",
+        AnitomyElementKind::VolumeTotal => b"Volume Total\0",
+    };
+    name.as_ptr().cast()
+}
@@ -1,9 +1,12 @@
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::Range};
 
-use crate::tokenizer::Token;
+use crate::{
+    rule::{Confidence, Rule},
+    tokenizer::Token,
+};
 
 /// The kind of element that has been parsed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -34,6 +37,10 @@ pub enum ElementKind {
     Part,
     Year,
     Date,
+    Unknown,
+    AlternativeTitle,
+    EpisodeTotal,
+    VolumeTotal,
 }
 
 impl ElementKind {
@@ -63,6 +70,77 @@ impl ElementKind {
             ElementKind::Part => "Part",
             ElementKind::Year => "Year",
             ElementKind::Date => "Date",
+            ElementKind::Unknown => "Unknown",
+            ElementKind::AlternativeTitle => "Alternative Title",
+            ElementKind::EpisodeTotal => "Episode Total",
+            ElementKind::VolumeTotal => "Volume Total",
+        }
+    }
+
+    /// Returns this ElementKind as the snake case key used by [`ElementObject`], e.g. `release_group`
+    pub const fn as_key(&self) -> &'static str {
+        match self {
+            ElementKind::AudioTerm => "audio_term",
+            ElementKind::DeviceCompatibility => "device_compatibility",
+            ElementKind::Episode => "episode",
+            ElementKind::EpisodeTitle => "episode_title",
+            ElementKind::EpisodeAlt => "episode_alt",
+            ElementKind::FileChecksum => "file_checksum",
+            ElementKind::FileExtension => "file_extension",
+            ElementKind::Language => "language",
+            ElementKind::Other => "other",
+            ElementKind::ReleaseGroup => "release_group",
+            ElementKind::ReleaseInformation => "release_information",
+            ElementKind::ReleaseVersion => "release_version",
+            ElementKind::Season => "season",
+            ElementKind::Source => "source",
+            ElementKind::Subtitles => "subtitles",
+            ElementKind::Title => "title",
+            ElementKind::Type => "type",
+            ElementKind::VideoResolution => "video_resolution",
+            ElementKind::VideoTerm => "video_term",
+            ElementKind::Volume => "volume",
+            ElementKind::Part => "part",
+            ElementKind::Year => "year",
+            ElementKind::Date => "date",
+            ElementKind::Unknown => "unknown",
+            ElementKind::AlternativeTitle => "alternative_title",
+            ElementKind::EpisodeTotal => "episode_total",
+            ElementKind::VolumeTotal => "volume_total",
+        }
+    }
+
+    /// Returns the ElementKind for a key returned by [`ElementKind::as_key`]
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "audio_term" => Some(ElementKind::AudioTerm),
+            "device_compatibility" => Some(ElementKind::DeviceCompatibility),
+            "episode" => Some(ElementKind::Episode),
+            "episode_title" => Some(ElementKind::EpisodeTitle),
+            "episode_alt" => Some(ElementKind::EpisodeAlt),
+            "file_checksum" => Some(ElementKind::FileChecksum),
+            "file_extension" => Some(ElementKind::FileExtension),
+            "language" => Some(ElementKind::Language),
+            "other" => Some(ElementKind::Other),
+            "release_group" => Some(ElementKind::ReleaseGroup),
+            "release_information" => Some(ElementKind::ReleaseInformation),
+            "release_version" => Some(ElementKind::ReleaseVersion),
+            "season" => Some(ElementKind::Season),
+            "source" => Some(ElementKind::Source),
+            "subtitles" => Some(ElementKind::Subtitles),
+            "title" => Some(ElementKind::Title),
+            "type" => Some(ElementKind::Type),
+            "video_resolution" => Some(ElementKind::VideoResolution),
+            "video_term" => Some(ElementKind::VideoTerm),
+            "volume" => Some(ElementKind::Volume),
+            "part" => Some(ElementKind::Part),
+            "year" => Some(ElementKind::Year),
+            "date" => Some(ElementKind::Date),
+            "unknown" => Some(ElementKind::Unknown),
+            "alternative_title" => Some(ElementKind::AlternativeTitle),
+            "episode_total" => Some(ElementKind::EpisodeTotal),
+            "volume_total" => Some(ElementKind::VolumeTotal),
+            _ => None,
         }
     }
 }
@@ -77,17 +155,88 @@ pub struct Element<'a> {
     pub(crate) value: Cow<'a, str>,
     #[cfg_attr(feature = "serde", serde(default, skip))]
     pub(crate) position: usize,
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(crate) span: Range<usize>,
+    #[cfg_attr(feature = "serde", serde(default = "default_rule", skip))]
+    pub(crate) rule: Rule,
+}
+
+#[cfg(feature = "serde")]
+const fn default_rule() -> Rule {
+    Rule::Keyword
 }
 
 impl<'a> Element<'a> {
-    pub(crate) fn new(kind: ElementKind, token: &Token<'a>) -> Self {
+    pub(crate) fn new(kind: ElementKind, rule: Rule, token: &Token<'a>) -> Self {
         Self {
             kind,
             value: token.value.into(),
             position: token.position,
+            span: token.span.clone(),
+            rule,
         }
     }
 
+    /// Creates an element from a value that was extracted from the given token.
+    ///
+    /// If the value is a substring of the token (e.g. `01` in `01v2`) then the span
+    /// is narrowed down to the substring, otherwise the span of the token is used.
+    pub(crate) fn with_value(
+        kind: ElementKind,
+        rule: Rule,
+        token: &Token<'a>,
+        value: &'a str,
+    ) -> Self {
+        let offset = (value.as_ptr() as usize)
+            .checked_sub(token.value.as_ptr() as usize)
+            .filter(|offset| offset + value.len() <= token.value.len());
+        let span = match offset {
+            Some(offset) => {
+                let start = token.span.start + offset;
+                start..(start + value.len())
+            }
+            None => token.span.clone(),
+        };
+        Self {
+            kind,
+            value: value.into(),
+            position: token.position,
+            span,
+            rule,
+        }
+    }
+
+    /// Creates an element from a value that was combined from multiple tokens.
+    ///
+    /// The span covers every token from the first to the last non-delimiter token.
+    pub(crate) fn from_tokens(
+        kind: ElementKind,
+        rule: Rule,
+        tokens: &[Token<'a>],
+        value: String,
+    ) -> Option<Self> {
+        let first = tokens.first()?;
+        let start = tokens
+            .iter()
+            .find(|t| t.is_not_delimiter())
+            .unwrap_or(first)
+            .span
+            .start;
+        let end = tokens
+            .iter()
+            .rfind(|t| t.is_not_delimiter())
+            .unwrap_or(first)
+            .span
+            .end;
+        Some(Self {
+            kind,
+            value: value.into(),
+            position: first.position,
+            span: start..end,
+            rule,
+        })
+    }
+
     /// Returns the kind of element
     pub fn kind(&self) -> ElementKind {
         self.kind
@@ -97,6 +246,25 @@ impl<'a> Element<'a> {
     pub fn value(&self) -> &str {
         &self.value
     }
+
+    /// Returns the rule of the parser that produced the element
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns how reliable the element is, based on the rule that produced it
+    pub fn confidence(&self) -> Confidence {
+        self.rule.confidence()
+    }
+
+    /// Returns the byte range of the element in the original input string.
+    ///
+    /// Note that the value of the element might not be equal to the input at this range,
+    /// e.g. titles have their delimiters replaced with spaces and keywords like `v2` are
+    /// stripped down to `2` while the span still points to the source text it came from.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
 }
 
 /// A helper type that turns a slice of [`Element`] objects into a flat struct with multiple elements.
@@ -105,6 +273,7 @@ impl<'a> Element<'a> {
 /// similar. Note that `Option` keys that are `None` are not serialized.
 ///
 /// If multiple elements are found with the same [`ElementKind`], then the latest one is stored.
+/// Use [`MultiElementObject`] to keep every value instead.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ElementObject<'a> {
@@ -228,6 +397,26 @@ pub struct ElementObject<'a> {
         serde(borrow, default, skip_serializing_if = "Option::is_none")
     )]
     pub date: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub unknown: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub alternative_title: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub episode_total: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub volume_total: Option<Cow<'a, str>>,
 }
 
 /// A helper type that turns a slice of [`Element`] objects into a flat struct with multiple elements.
@@ -236,6 +425,7 @@ pub struct ElementObject<'a> {
 /// similar. Note that `Option` keys that are `None` are not serialized.
 ///
 /// If multiple elements are found with the same [`ElementKind`], then the latest one is stored.
+/// Use [`OwnedMultiElementObject`] to keep every value instead.
 ///
 /// Unlike [`ElementObject`], this one only contains owned strings which makes it easier to work
 /// with for the deserialization use case.
@@ -357,6 +547,26 @@ pub struct OwnedElementObject {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub date: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub unknown: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub alternative_title: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub episode_total: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub volume_total: Option<String>,
 }
 
 macro_rules! impl_from_iterator {
@@ -444,6 +654,484 @@ impl_from_iterator! {
     Part => part,
     Year => year,
     Date => date,
+    Unknown => unknown,
+    AlternativeTitle => alternative_title,
+    EpisodeTotal => episode_total,
+    VolumeTotal => volume_total,
+}
+
+/// A helper type that turns a slice of [`Element`] objects into a flat struct with multiple elements,
+/// without losing any values.
+///
+/// This is like [`ElementObject`] except that the kinds which can be found multiple times in a
+/// name (e.g. the episodes of `01-12` or the audio terms of `FLAC AAC`) are stored as a `Vec` of
+/// every value in order of appearance. The other kinds still store the latest value.
+///
+/// When serialized, a repeatable kind with a single value is encoded as a string and one with
+/// multiple values as an array of strings, which is the same format as `tests/data.json` uses.
+/// Both forms are accepted when deserializing. Empty `Vec` and `None` keys are not serialized.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiElementObject<'a> {
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub audio_term: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub device_compatibility: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub episode: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub episode_alt: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub episode_title: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub file_checksum: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub file_extension: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub language: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub other: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub release_group: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub release_information: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub release_version: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub season: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub source: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub subtitles: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub title: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "type",
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "multi_value"
+        )
+    )]
+    pub kind: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub video_resolution: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub video_term: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub volume: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub part: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub year: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub date: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub unknown: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub alternative_title: Vec<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub episode_total: Option<Cow<'a, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(borrow, default, skip_serializing_if = "Option::is_none")
+    )]
+    pub volume_total: Option<Cow<'a, str>>,
+}
+
+/// An owned version of [`MultiElementObject`].
+///
+/// This only contains owned strings which makes it easier to work with for the deserialization
+/// use case.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedMultiElementObject {
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub audio_term: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub device_compatibility: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub episode: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub episode_alt: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub episode_title: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub file_checksum: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub file_extension: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub language: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub other: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub release_group: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub release_information: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub release_version: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub season: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub source: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub subtitles: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub title: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "type",
+            default,
+            skip_serializing_if = "Vec::is_empty",
+            with = "multi_value"
+        )
+    )]
+    pub kind: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub video_resolution: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub video_term: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub volume: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub part: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub year: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub date: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub unknown: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", with = "multi_value")
+    )]
+    pub alternative_title: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub episode_total: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub volume_total: Option<String>,
+}
+
+macro_rules! impl_from_iterator_multi {
+    (
+        single { $($name:ident => $mapped:ident),+$(,)? }
+        multi { $($multi_name:ident => $multi_mapped:ident),+$(,)? }
+    ) => {
+        impl<'a, 'b: 'a> FromIterator<&'b Element<'a>> for MultiElementObject<'a> {
+            fn from_iter<T: IntoIterator<Item = &'b Element<'a>>>(iter: T) -> Self {
+                use std::borrow::Borrow;
+                let mut object = Self::default();
+                for element in iter {
+                    let value = std::borrow::Cow::Borrowed(element.value.borrow());
+                    match element.kind {
+                        $($crate::ElementKind::$name => object.$mapped = Some(value),)+
+                        $($crate::ElementKind::$multi_name => object.$multi_mapped.push(value),)+
+                    }
+                }
+                object
+            }
+        }
+
+        impl<'a> FromIterator<Element<'a>> for MultiElementObject<'a> {
+            fn from_iter<T: IntoIterator<Item = Element<'a>>>(iter: T) -> Self {
+                let mut object = Self::default();
+                for element in iter {
+                    let value = element.value;
+                    match element.kind {
+                        $($crate::ElementKind::$name => object.$mapped = Some(value),)+
+                        $($crate::ElementKind::$multi_name => object.$multi_mapped.push(value),)+
+                    }
+                }
+                object
+            }
+        }
+
+        impl<'a, 'b: 'a> FromIterator<&'b Element<'a>> for OwnedMultiElementObject {
+            fn from_iter<T: IntoIterator<Item = &'b Element<'a>>>(iter: T) -> Self {
+                let mut object = Self::default();
+                for element in iter {
+                    let value = String::from(&element.value[..]);
+                    match element.kind {
+                        $($crate::ElementKind::$name => object.$mapped = Some(value),)+
+                        $($crate::ElementKind::$multi_name => object.$multi_mapped.push(value),)+
+                    }
+                }
+                object
+            }
+        }
+
+        impl<'a> FromIterator<Element<'a>> for OwnedMultiElementObject {
+            fn from_iter<T: IntoIterator<Item = Element<'a>>>(iter: T) -> Self {
+                let mut object = Self::default();
+                for element in iter {
+                    let value = element.value.into_owned();
+                    match element.kind {
+                        $($crate::ElementKind::$name => object.$mapped = Some(value),)+
+                        $($crate::ElementKind::$multi_name => object.$multi_mapped.push(value),)+
+                    }
+                }
+                object
+            }
+        }
+    };
+}
+
+impl_from_iterator_multi! {
+    single {
+        DeviceCompatibility => device_compatibility,
+        EpisodeAlt => episode_alt,
+        EpisodeTitle => episode_title,
+        FileChecksum => file_checksum,
+        FileExtension => file_extension,
+        ReleaseGroup => release_group,
+        ReleaseInformation => release_information,
+        Title => title,
+        VideoResolution => video_resolution,
+        Part => part,
+        Year => year,
+        Date => date,
+        Unknown => unknown,
+        EpisodeTotal => episode_total,
+        VolumeTotal => volume_total,
+    }
+    multi {
+        AudioTerm => audio_term,
+        Episode => episode,
+        Language => language,
+        Other => other,
+        ReleaseVersion => release_version,
+        Season => season,
+        Source => source,
+        Subtitles => subtitles,
+        Type => kind,
+        VideoTerm => video_term,
+        Volume => volume,
+        AlternativeTitle => alternative_title,
+    }
+}
+
+/// (De)serializes a `Vec` of values as a single string if there is only one value and as an
+/// array of strings otherwise.
+#[cfg(feature = "serde")]
+mod multi_value {
+    use std::marker::PhantomData;
+
+    use serde::{
+        de::{value::SeqAccessDeserializer, SeqAccess, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    pub(super) fn serialize<S, T>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        match values {
+            [value] => value.serialize(serializer),
+            values => values.serialize(serializer),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + From<&'de str> + From<String>,
+    {
+        deserializer.deserialize_any(MultiValueVisitor(PhantomData))
+    }
+
+    struct MultiValueVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for MultiValueVisitor<T>
+    where
+        T: Deserialize<'de> + From<&'de str> + From<String>,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("string or array of strings")
+        }
+
+        fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(vec![T::from(v)])
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(vec![T::from(v.to_owned())])
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(vec![T::from(v)])
+        }
+
+        fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            Vec::<T>::deserialize(SeqAccessDeserializer::new(seq))
+        }
+    }
 }
 
 #[cfg(feature = "wasm")]
@@ -451,14 +1139,24 @@ impl_from_iterator! {
 pub struct JsElement {
     pub kind: ElementKind,
     pub value: String,
+    /// The start of the element in the input, in UTF-16 code units like JavaScript strings
+    pub start: usize,
+    /// The end of the element in the input, in UTF-16 code units like JavaScript strings
+    pub end: usize,
 }
 
 #[cfg(feature = "wasm")]
-impl<'a> From<Element<'a>> for JsElement {
-    fn from(el: Element<'a>) -> Self {
+impl JsElement {
+    /// Converts an element of `input`, where the byte span becomes a span of UTF-16 code
+    /// units so that it can be used with e.g. `String.prototype.slice`.
+    pub(crate) fn new(input: &str, el: Element<'_>) -> Self {
+        let start = input[..el.span.start].encode_utf16().count();
+        let end = start + input[el.span.clone()].encode_utf16().count();
         Self {
             kind: el.kind,
             value: el.value.into_owned(),
+            start,
+            end,
         }
     }
 }
@@ -0,0 +1,155 @@
+use crate::{element::Element, rule::Rule, tokenizer::Token};
+
+/// An element along with the tokens that were consumed to produce it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplainedElement<'a> {
+    pub element: Element<'a>,
+    pub tokens: Vec<Token<'a>>,
+}
+
+impl<'a> ExplainedElement<'a> {
+    /// Returns the rule of the parser that produced the element
+    pub fn rule(&self) -> Rule {
+        self.element.rule
+    }
+}
+
+/// The result of a parse along with how each element was found.
+///
+/// This is meant for debugging and triaging bad parses, see [`crate::parse_explained`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    /// The parsed elements in the same order as [`crate::parse`] returns them
+    pub elements: Vec<ExplainedElement<'a>>,
+    /// The rules that were tried but did not match, in the order they were tried
+    pub rejected: Vec<Rule>,
+}
+
+impl<'a> Explanation<'a> {
+    /// Returns an iterator over the parsed elements without the explanation
+    pub fn elements(&self) -> impl Iterator<Item = &Element<'a>> {
+        self.elements.iter().map(|e| &e.element)
+    }
+}
+
+/// Records which tokens and rules were involved in every stage of the parser.
+#[derive(Debug, Default)]
+pub(crate) struct Trace {
+    /// The indices of the consumed tokens, parallel to the parsed elements
+    consumed: Vec<Vec<usize>>,
+    rejected: Vec<Rule>,
+}
+
+impl Trace {
+    /// Runs a single stage of the parser and attributes its results.
+    ///
+    /// The `rules` are the rules of the stage in the order they are tried. Rules that come
+    /// before the last one that produced an element are rejected. If nothing was produced then
+    /// every rule is rejected, unless `report_unmatched` is false.
+    pub(crate) fn stage<'a, F>(
+        &mut self,
+        tokens: &mut [Token<'a>],
+        results: &mut Vec<Element<'a>>,
+        rules: &[Rule],
+        report_unmatched: bool,
+        f: F,
+    ) where
+        F: FnOnce(&mut [Token<'a>], &mut Vec<Element<'a>>),
+    {
+        let known: Vec<bool> = tokens.iter().map(|t| t.is_identified()).collect();
+        let start = results.len();
+        f(tokens, results);
+
+        let produced = &results[start..];
+        let matched = rules
+            .iter()
+            .rposition(|rule| produced.iter().any(|e| e.rule == *rule));
+        let tried = match matched {
+            Some(index) => &rules[..index],
+            None if report_unmatched => rules,
+            None => &[],
+        };
+        for rule in tried {
+            if !produced.iter().any(|e| e.rule == *rule) && !self.rejected.contains(rule) {
+                self.rejected.push(*rule);
+            }
+        }
+
+        let mut consumed = vec![Vec::new(); produced.len()];
+        for (index, token) in tokens.iter().enumerate() {
+            if produced.is_empty() || known[index] || !token.is_identified() {
+                continue;
+            }
+            let owners: Vec<usize> = (0..produced.len())
+                .filter(|&i| overlaps(&produced[i], token))
+                .collect();
+            if owners.is_empty() {
+                // Tokens such as delimiters or keywords (e.g. `Season` in `Season 2`) are
+                // consumed without being part of the value, so they go to the closest element.
+                let closest = (0..produced.len())
+                    .min_by_key(|&i| produced[i].position.abs_diff(token.position))
+                    .unwrap_or_default();
+                consumed[closest].push(index);
+            } else {
+                for owner in owners {
+                    consumed[owner].push(index);
+                }
+            }
+        }
+
+        // Some rules do not mark their tokens as known (e.g. ambiguous keywords),
+        // in which case the tokens they were extracted from are used instead.
+        for (element, consumed) in produced.iter().zip(consumed.iter_mut()) {
+            if consumed.is_empty() {
+                consumed.extend(
+                    tokens
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, t)| overlaps(element, t))
+                        .map(|(i, _)| i),
+                );
+            }
+        }
+
+        self.consumed.extend(consumed);
+    }
+
+    /// Sorts the results by their position while keeping the trace in sync.
+    pub(crate) fn sort(&mut self, results: &mut Vec<Element<'_>>) {
+        let mut order: Vec<usize> = (0..results.len()).collect();
+        order.sort_by_key(|&i| results[i].position);
+
+        let mut elements: Vec<_> = std::mem::take(results).into_iter().map(Some).collect();
+        let mut consumed = std::mem::take(&mut self.consumed);
+        for index in order {
+            if let Some(element) = elements[index].take() {
+                results.push(element);
+                self.consumed.push(std::mem::take(&mut consumed[index]));
+            }
+        }
+    }
+
+    pub(crate) fn into_explanation<'a>(
+        self,
+        tokens: &[Token<'a>],
+        results: Vec<Element<'a>>,
+    ) -> Explanation<'a> {
+        let elements = results
+            .into_iter()
+            .zip(self.consumed)
+            .map(|(element, consumed)| ExplainedElement {
+                element,
+                tokens: consumed.into_iter().map(|i| tokens[i].clone()).collect(),
+            })
+            .collect();
+        Explanation {
+            elements,
+            rejected: self.rejected,
+        }
+    }
+}
+
+fn overlaps(element: &Element<'_>, token: &Token<'_>) -> bool {
+    element.position == token.position
+        || (token.span.start < element.span.end && element.span.start < token.span.end)
+}
@@ -0,0 +1,353 @@
+use std::{fmt, str::FromStr};
+
+use crate::element::{Element, ElementKind};
+
+/// An error that occurred while parsing a [`Template`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A placeholder refers to a key that isn't an [`ElementKind`] key, e.g. `{titel}`
+    UnknownKey(String),
+    /// A placeholder has a transform that doesn't exist, e.g. `{title:shout}`
+    UnknownTransform(String),
+    /// A `{` was never closed
+    Unclosed,
+    /// A `}` was found without a matching `{`, use `}}` for a literal one
+    Unmatched,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownKey(key) => write!(f, "unknown key `{key}`"),
+            TemplateError::UnknownTransform(name) => write!(f, "unknown transform `{name}`"),
+            TemplateError::Unclosed => f.write_str("unclosed `{`"),
+            TemplateError::Unmatched => f.write_str("unmatched `}`"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Transform {
+    /// Zero pads the whole part of every number to the given width
+    Pad(usize),
+    Upper,
+    Lower,
+    /// Only keeps the first value, e.g. `01` of `01-12`
+    First,
+    /// Only keeps the last value, e.g. `12` of `01-12`
+    Last,
+    /// Joins multiple values with the given separator instead of the default one
+    Join(String),
+}
+
+impl Transform {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "upper" => Some(Self::Upper),
+            "lower" => Some(Self::Lower),
+            "first" => Some(Self::First),
+            "last" => Some(Self::Last),
+            _ if name.starts_with("join(") && name.ends_with(')') => {
+                Some(Self::Join(name[5..name.len() - 1].to_owned()))
+            }
+            _ if !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()) => {
+                name.parse().ok().map(Self::Pad)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Value {
+        kind: ElementKind,
+        transforms: Vec<Transform>,
+    },
+    /// Renders the body only if the kind was (or with `present: false`, wasn't) found
+    Conditional {
+        kind: ElementKind,
+        present: bool,
+        body: Vec<Segment>,
+    },
+}
+
+/// A template that renders parsed elements back into a name.
+///
+/// Placeholders are the [`ElementKind::as_key`] keys in braces, e.g. `{title}`, and render as
+/// an empty string if the kind wasn't found. They can have transforms separated by colons:
+///
+/// - A number zero pads every value to that width, e.g. `{episode:02}` turns `1` into `01`
+///   and `1.5` into `01.5`. Values that aren't numbers are left as is.
+/// - `upper` and `lower` change the case of the value.
+/// - `first` and `last` only keep the first or last value of a kind that was found
+///   multiple times.
+/// - `join(text)` joins multiple values with `text`, e.g. `E{episode:02:join(-E)}` renders
+///   `E01-E02`. The text can't contain `:` or `}`.
+///
+/// If a kind was found multiple times then the values are joined, numbers with `-` so that
+/// episode ranges render as e.g. `01-12` and everything else with a space.
+///
+/// Conditionals render their body only if the kind was found, `{?key:body}`, or if it wasn't,
+/// `{!key:body}`. The body is a template itself. Literal braces are written as `{{` and `}}`.
+///
+/// ```
+/// use anitomy::Template;
+///
+/// let template: Template = "{title} - {episode:02}{?release_group: [{release_group}]}.{file_extension}"
+///     .parse()
+///     .unwrap();
+/// let elements = anitomy::parse("[Group] Title - 1.mkv");
+/// assert_eq!(template.render(&elements), "Title - 01 [Group].mkv");
+///
+/// let template = Template::new("{title} - S{season:02}E{episode:02}.{file_extension}").unwrap();
+/// let elements = anitomy::parse("[Group] Title S2 - 5.mkv");
+/// assert_eq!(template.render(&elements), "Title - S02E05.mkv");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parses a template, see [`Template`] for the syntax.
+    pub fn new(template: &str) -> Result<Self, TemplateError> {
+        let mut chars = template.chars().peekable();
+        let segments = parse_segments(&mut chars, false)?;
+        Ok(Self { segments })
+    }
+
+    /// Renders the elements into a string, typically the ones returned by [`crate::parse`].
+    pub fn render(&self, elements: &[Element<'_>]) -> String {
+        let mut output = String::new();
+        render_segments(&self.segments, elements, &mut output);
+        output
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+/// Parses segments until the end of the input, or until the closing `}` if `nested` is true
+fn parse_segments(chars: &mut Chars<'_>, nested: bool) -> Result<Vec<Segment>, TemplateError> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    loop {
+        match chars.next() {
+            None if nested => return Err(TemplateError::Unclosed),
+            None => break,
+            Some('{') if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            Some('}') if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            Some('}') if nested => break,
+            Some('}') => return Err(TemplateError::Unmatched),
+            Some('{') => {
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(parse_placeholder(chars)?);
+            }
+            Some(c) => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+/// Parses a placeholder after its opening `{`, up to and including its closing `}`
+fn parse_placeholder(chars: &mut Chars<'_>) -> Result<Segment, TemplateError> {
+    let present = match chars.peek() {
+        Some('?') => Some(true),
+        Some('!') => Some(false),
+        _ => None,
+    };
+    if present.is_some() {
+        chars.next();
+    }
+
+    let mut key = String::new();
+    let terminator = loop {
+        match chars.next() {
+            None => return Err(TemplateError::Unclosed),
+            Some(c @ (':' | '}')) => break c,
+            Some(c) => key.push(c),
+        }
+    };
+    let kind = ElementKind::from_key(key.trim()).ok_or(TemplateError::UnknownKey(key))?;
+
+    if let Some(present) = present {
+        let body = match terminator {
+            ':' => parse_segments(chars, true)?,
+            _ => Vec::new(),
+        };
+        return Ok(Segment::Conditional {
+            kind,
+            present,
+            body,
+        });
+    }
+
+    let mut transforms = Vec::new();
+    if terminator == ':' {
+        let mut spec = String::new();
+        loop {
+            match chars.next() {
+                None => return Err(TemplateError::Unclosed),
+                Some('}') => break,
+                Some(c) => spec.push(c),
+            }
+        }
+        for name in spec.split(':') {
+            // The separator of `join` is kept as is, e.g. `join( & )`
+            let name = if name.trim_start().starts_with("join(") {
+                name.trim_start()
+            } else {
+                name.trim()
+            };
+            let transform = Transform::from_name(name)
+                .ok_or_else(|| TemplateError::UnknownTransform(name.to_owned()))?;
+            transforms.push(transform);
+        }
+    }
+    Ok(Segment::Value { kind, transforms })
+}
+
+fn render_segments(segments: &[Segment], elements: &[Element<'_>], output: &mut String) {
+    for segment in segments {
+        match segment {
+            Segment::Literal(literal) => output.push_str(literal),
+            Segment::Value { kind, transforms } => {
+                output.push_str(&render_value(*kind, transforms, elements));
+            }
+            Segment::Conditional {
+                kind,
+                present,
+                body,
+            } => {
+                if elements.iter().any(|e| e.kind == *kind) == *present {
+                    render_segments(body, elements, output);
+                }
+            }
+        }
+    }
+}
+
+fn render_value(kind: ElementKind, transforms: &[Transform], elements: &[Element<'_>]) -> String {
+    let mut separator = match kind {
+        ElementKind::Episode
+        | ElementKind::EpisodeAlt
+        | ElementKind::Season
+        | ElementKind::Volume
+        | ElementKind::Part => "-",
+        _ => " ",
+    };
+    let mut values: Vec<String> = elements
+        .iter()
+        .filter(|e| e.kind == kind)
+        .map(|e| e.value.to_string())
+        .collect();
+
+    for transform in transforms {
+        match transform {
+            Transform::First => values.truncate(1),
+            Transform::Last => {
+                values.drain(..values.len().saturating_sub(1));
+            }
+            Transform::Pad(width) => {
+                for value in &mut values {
+                    *value = pad_number(value, *width);
+                }
+            }
+            Transform::Upper => {
+                for value in &mut values {
+                    *value = value.to_uppercase();
+                }
+            }
+            Transform::Lower => {
+                for value in &mut values {
+                    *value = value.to_lowercase();
+                }
+            }
+            Transform::Join(text) => separator = text,
+        }
+    }
+
+    values.join(separator)
+}
+
+/// Zero pads the whole part of a number such as `1` or `1.5`, anything else is left as is
+fn pad_number(value: &str, width: usize) -> String {
+    let whole = value.split_once('.').map_or(value, |(whole, _)| whole);
+    if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+        return value.to_owned();
+    }
+    format!("{}{value}", "0".repeat(width.saturating_sub(whole.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_syntax() {
+        let elements = crate::parse("[Group] Title - 01-12 [1080p FLAC AAC].mkv");
+        let render = |template: &str| Template::new(template).unwrap().render(&elements);
+
+        assert_eq!(render("{title} - {episode}"), "Title - 01-12");
+        assert_eq!(render("{episode:first:3}-{episode:last}"), "001-12");
+        assert_eq!(render("{audio_term:lower}"), "flac aac");
+        assert_eq!(render("e{episode:join(-e):3}"), "e001-e012");
+        assert_eq!(render("{audio_term:join( & )}"), "FLAC & AAC");
+        assert_eq!(render("{title:upper}{?season: S{season}}"), "TITLE");
+        assert_eq!(
+            render("{!season:S01}{?file_extension:.{file_extension}}"),
+            "S01.mkv"
+        );
+        assert_eq!(render("{{{title}}}"), "{Title}");
+        assert_eq!(render("{ year }"), "");
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert_eq!(
+            Template::new("{titel}"),
+            Err(TemplateError::UnknownKey("titel".to_owned()))
+        );
+        assert_eq!(
+            Template::new("{title:shout}"),
+            Err(TemplateError::UnknownTransform("shout".to_owned()))
+        );
+        assert_eq!(Template::new("{title"), Err(TemplateError::Unclosed));
+        assert_eq!(
+            Template::new("{?title: [{title}]"),
+            Err(TemplateError::Unclosed)
+        );
+        assert_eq!(Template::new("title}"), Err(TemplateError::Unmatched));
+    }
+
+    #[test]
+    fn test_pad_number() {
+        assert_eq!(pad_number("1", 2), "01");
+        assert_eq!(pad_number("1.5", 2), "01.5");
+        assert_eq!(pad_number("123", 2), "123");
+        assert_eq!(pad_number("S2", 2), "S2");
+        assert_eq!(pad_number("第五", 2), "第五");
+    }
+}
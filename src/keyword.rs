@@ -1,8 +1,13 @@
+use std::{collections::HashMap, sync::OnceLock};
+
 use phf::phf_map;
-use uncased::UncasedStr;
+use uncased::{Uncased, UncasedStr};
+
+use crate::trie::KeywordTrie;
 
+/// The kind of keyword that the tokenizer recognises
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) enum KeywordKind {
+pub enum KeywordKind {
     AudioChannels,
     AudioCodec,
     AudioLanguage,
@@ -30,8 +35,9 @@ pub(crate) enum KeywordKind {
     Volume,
 }
 
+/// A keyword entry with its kind and flags.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct Keyword {
+pub struct Keyword {
     pub(crate) kind: KeywordKind,
     flags: u8,
 }
@@ -40,33 +46,157 @@ impl Keyword {
     const AMBIGUOUS: u8 = 1 << 0;
     const UNBOUNDED: u8 = 1 << 1;
 
-    pub(crate) const fn new(kind: KeywordKind) -> Self {
+    /// Creates a keyword that is neither ambiguous nor unbounded.
+    pub const fn new(kind: KeywordKind) -> Self {
         Self { kind, flags: 0 }
     }
 
-    pub(crate) const fn unbounded(kind: KeywordKind) -> Self {
+    /// Creates a keyword that can be matched without a delimiter after it (e.g. `1080p` in `1080pHEVC`).
+    pub const fn unbounded(kind: KeywordKind) -> Self {
         Self {
             kind,
             flags: Self::UNBOUNDED,
         }
     }
 
-    pub(crate) const fn ambiguous(kind: KeywordKind) -> Self {
+    /// Creates a keyword that may also be part of a title (e.g. `ESP` in `Tokyo ESP`).
+    pub const fn ambiguous(kind: KeywordKind) -> Self {
         Self {
             kind,
             flags: Self::AMBIGUOUS,
         }
     }
 
-    pub(crate) const fn is_ambiguous(&self) -> bool {
+    /// A builder method to toggle whether the keyword is ambiguous.
+    pub const fn with_ambiguous(mut self, toggle: bool) -> Self {
+        if toggle {
+            self.flags |= Self::AMBIGUOUS;
+        } else {
+            self.flags &= !Self::AMBIGUOUS;
+        }
+        self
+    }
+
+    /// A builder method to toggle whether the keyword is unbounded.
+    pub const fn with_unbounded(mut self, toggle: bool) -> Self {
+        if toggle {
+            self.flags |= Self::UNBOUNDED;
+        } else {
+            self.flags &= !Self::UNBOUNDED;
+        }
+        self
+    }
+
+    /// Returns the kind of keyword
+    pub const fn kind(&self) -> KeywordKind {
+        self.kind
+    }
+
+    /// Returns a bool indicating whether the keyword may also be part of a title.
+    pub const fn is_ambiguous(&self) -> bool {
         (self.flags & Self::AMBIGUOUS) == Self::AMBIGUOUS
     }
 
-    pub(crate) const fn is_bounded(&self) -> bool {
+    /// Returns a bool indicating whether the keyword must be followed by a delimiter or bracket.
+    pub const fn is_bounded(&self) -> bool {
         (self.flags & Self::UNBOUNDED) != Self::UNBOUNDED
     }
 }
 
+/// A set of keywords that the tokenizer uses to recognise tokens.
+///
+/// This starts off with the built-in keywords and allows adding, removing or overriding
+/// entries at runtime. Keys are matched case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct KeywordSet {
+    /// Entries that take precedence over the built-in ones, `None` marks a removed keyword.
+    overrides: HashMap<Uncased<'static>, Option<Keyword>>,
+    /// The trie for the keywords with overrides applied, built on first use.
+    trie: OnceLock<KeywordTrie>,
+}
+
+impl KeywordSet {
+    /// Creates a keyword set containing the built-in keywords.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the keyword associated with the key.
+    pub fn get(&self, key: &str) -> Option<Keyword> {
+        let key = UncasedStr::new(key);
+        match self.overrides.get(key) {
+            Some(keyword) => *keyword,
+            None => KEYWORDS.get(key).copied(),
+        }
+    }
+
+    /// Returns a bool indicating whether the key is a keyword.
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Adds a keyword to the set, overriding any previous keyword with the same key.
+    ///
+    /// Returns the keyword that was previously associated with the key.
+    pub fn insert(&mut self, key: impl Into<String>, keyword: Keyword) -> Option<Keyword> {
+        let key = Uncased::from(key.into());
+        let previous = self.get(key.as_str());
+        self.overrides.insert(key, Some(keyword));
+        self.trie = OnceLock::new();
+        previous
+    }
+
+    /// Removes a keyword from the set, including built-in keywords.
+    ///
+    /// Returns the keyword that was previously associated with the key.
+    pub fn remove(&mut self, key: &str) -> Option<Keyword> {
+        let previous = self.get(key);
+        if KEYWORDS.contains_key(UncasedStr::new(key)) {
+            self.overrides.insert(Uncased::from(key.to_owned()), None);
+        } else {
+            self.overrides.remove(UncasedStr::new(key));
+        }
+        self.trie = OnceLock::new();
+        previous
+    }
+
+    /// Returns an iterator over every key and keyword in the set.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Keyword)> + '_ {
+        let builtin = KEYWORDS
+            .entries()
+            .filter(|(key, _)| !self.overrides.contains_key(**key))
+            .map(|(key, keyword)| (key.as_str(), *keyword));
+        let overrides = self
+            .overrides
+            .iter()
+            .filter_map(|(key, keyword)| keyword.map(|k| (key.as_str(), k)));
+        builtin.chain(overrides)
+    }
+
+    fn trie(&self) -> &KeywordTrie {
+        static BUILTIN: OnceLock<KeywordTrie> = OnceLock::new();
+        if self.overrides.is_empty() {
+            BUILTIN.get_or_init(|| {
+                KEYWORDS
+                    .entries()
+                    .map(|(key, keyword)| (key.as_str(), *keyword))
+                    .collect()
+            })
+        } else {
+            self.trie.get_or_init(|| self.iter().collect())
+        }
+    }
+
+    /// Returns the longest keyword that the input starts with, along with the matched prefix.
+    ///
+    /// This does not check whether the keyword is followed by a boundary.
+    pub fn longest_prefix<'a>(&self, input: &'a str) -> Option<(&'a str, Keyword)> {
+        self.trie()
+            .longest_prefix(input)
+            .map(|(len, keyword)| (&input[..len], keyword))
+    }
+}
+
 pub(crate) static KEYWORDS: phf::Map<&'static UncasedStr, Keyword> = phf_map! {
     // Audio
     //
@@ -85,6 +215,9 @@ pub(crate) static KEYWORDS: phf::Map<&'static UncasedStr, Keyword> = phf_map! {
     UncasedStr::new("TrueHD5.1")    =>    Keyword::new(KeywordKind::AudioChannels),
     UncasedStr::new("DD5.1")        =>    Keyword::new(KeywordKind::AudioChannels),
     UncasedStr::new("DD2.0")        =>    Keyword::new(KeywordKind::AudioChannels),
+    UncasedStr::new("DDP2.0")       =>    Keyword::new(KeywordKind::AudioChannels),
+    UncasedStr::new("DDP5.1")       =>    Keyword::new(KeywordKind::AudioChannels),
+    UncasedStr::new("DDP7.1")       =>    Keyword::new(KeywordKind::AudioChannels),
     // Codec
     UncasedStr::new("AAC")          =>    Keyword::new(KeywordKind::AudioCodec),
     UncasedStr::new("AAC2.0")       =>    Keyword::new(KeywordKind::AudioCodec),
@@ -94,6 +227,7 @@ pub(crate) static KEYWORDS: phf::Map<&'static UncasedStr, Keyword> = phf_map! {
     UncasedStr::new("AC3")          =>    Keyword::new(KeywordKind::AudioCodec),
     UncasedStr::new("EAC3")         =>    Keyword::new(KeywordKind::AudioCodec),
     UncasedStr::new("E-AC-3")       =>    Keyword::new(KeywordKind::AudioCodec),
+    UncasedStr::new("DDP")          =>    Keyword::new(KeywordKind::AudioCodec),
     UncasedStr::new("FLAC")         =>    Keyword::new(KeywordKind::AudioCodec),
     UncasedStr::new("FLACX2")       =>    Keyword::new(KeywordKind::AudioCodec),
     UncasedStr::new("FLACX3")       =>    Keyword::new(KeywordKind::AudioCodec),
@@ -200,6 +334,8 @@ pub(crate) static KEYWORDS: phf::Map<&'static UncasedStr, Keyword> = phf_map! {
     UncasedStr::new("Final")        =>    Keyword::ambiguous(KeywordKind::ReleaseInformation),  // e.g. "Final Approach"
     UncasedStr::new("Patch")        =>    Keyword::new(KeywordKind::ReleaseInformation),
     UncasedStr::new("Remux")        =>    Keyword::new(KeywordKind::ReleaseInformation),
+    UncasedStr::new("Repack")       =>    Keyword::new(KeywordKind::ReleaseInformation),
+    UncasedStr::new("Rerip")        =>    Keyword::new(KeywordKind::ReleaseInformation),
 
     // Release version
     UncasedStr::new("v0")           =>    Keyword::new(KeywordKind::ReleaseVersion),
@@ -243,6 +379,7 @@ pub(crate) static KEYWORDS: phf::Map<&'static UncasedStr, Keyword> = phf_map! {
     UncasedStr::new("Web-DL")       =>    Keyword::new(KeywordKind::Source),
     UncasedStr::new("WebRip")       =>    Keyword::new(KeywordKind::Source),
     UncasedStr::new("AMZN")         =>    Keyword::new(KeywordKind::Source),  // Amazon Prime
+    UncasedStr::new("ATVP")         =>    Keyword::new(KeywordKind::Source),  // Apple TV+
     UncasedStr::new("CR")           =>    Keyword::new(KeywordKind::Source),  // Crunchyroll
     UncasedStr::new("Crunchyroll")  =>    Keyword::new(KeywordKind::Source),
     UncasedStr::new("DSNP")         =>    Keyword::new(KeywordKind::Source),  // Disney+
@@ -250,9 +387,12 @@ pub(crate) static KEYWORDS: phf::Map<&'static UncasedStr, Keyword> = phf_map! {
     UncasedStr::new("Funimation")   =>    Keyword::new(KeywordKind::Source),
     UncasedStr::new("HIDI")         =>    Keyword::new(KeywordKind::Source),  // Hidive
     UncasedStr::new("Hidive")       =>    Keyword::new(KeywordKind::Source),
+    UncasedStr::new("HMAX")         =>    Keyword::new(KeywordKind::Source),  // HBO Max
     UncasedStr::new("Hulu")         =>    Keyword::new(KeywordKind::Source),
     UncasedStr::new("Netflix")      =>    Keyword::new(KeywordKind::Source),
     UncasedStr::new("NF")           =>    Keyword::new(KeywordKind::Source),  // Netflix
+    UncasedStr::new("PCOK")         =>    Keyword::new(KeywordKind::Source),  // Peacock
+    UncasedStr::new("PMTP")         =>    Keyword::new(KeywordKind::Source),  // Paramount+
     UncasedStr::new("VRV")          =>    Keyword::new(KeywordKind::Source),
     UncasedStr::new("YouTube")      =>    Keyword::new(KeywordKind::Source),
 
@@ -1,15 +1,45 @@
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+pub(crate) mod batch;
+pub(crate) mod candidates;
+#[cfg(feature = "capi")]
+pub mod capi;
 pub(crate) mod element;
+pub(crate) mod explain;
+pub(crate) mod format;
 pub(crate) mod keyword;
 pub(crate) mod options;
+pub mod organize;
 pub(crate) mod parser;
+pub(crate) mod path;
+pub(crate) mod preset;
+#[cfg(feature = "python")]
+mod python;
+pub(crate) mod release;
+pub(crate) mod rule;
 pub(crate) mod tokenizer;
+pub(crate) mod trie;
 pub(crate) mod utils;
+#[cfg(feature = "wasm")]
+mod wasm;
 
-pub use element::{Element, ElementKind, ElementObject, OwnedElementObject};
+pub use batch::BatchEntry;
+pub use candidates::Candidate;
+pub use element::{
+    Element, ElementKind, ElementObject, MultiElementObject, OwnedElementObject,
+    OwnedMultiElementObject,
+};
+pub use explain::{ExplainedElement, Explanation};
+pub use format::{Template, TemplateError};
+pub use keyword::{Keyword, KeywordKind, KeywordSet};
 pub use options::Options;
+pub use parser::Parser;
+pub use path::{ParsedPath, PathElement};
+pub use preset::{MediaKind, Preset};
+pub use release::{EpisodeNumber, EpisodeRange, Numbering, ParsedRelease};
+pub use rule::{Confidence, Rule};
+pub use tokenizer::{Token, TokenKind};
 
 /// Parses a string into its element components with the given options.
 ///
@@ -19,8 +49,7 @@ pub use options::Options;
 /// For best results, the string should be in composed form (NFC/NFKC)
 /// for the tokenizer to work properly.
 pub fn parse_with_options(input: &str, options: Options) -> Vec<Element<'_>> {
-    let tokens = tokenizer::Tokenizer::new(input).tokens();
-    parser::parse_with_options(tokens, options)
+    Parser::new(options).parse(input)
 }
 
 /// Parses a string into its element components with the given options
@@ -28,11 +57,115 @@ pub fn parse(input: &str) -> Vec<Element<'_>> {
     parse_with_options(input, Options::default())
 }
 
+/// Splits a string into its tokens using the built-in keywords.
+///
+/// Concatenating the values of the returned tokens reproduces the input exactly.
+/// This is mostly useful for debugging or for building custom heuristics on top of the
+/// tokenizer, see [`Parser::tokenize`] to use custom keywords.
+pub fn tokenize(input: &str) -> Vec<Token<'_>> {
+    Parser::default().tokenize(input)
+}
+
+/// Parses a string into its element components along with how each of them was found.
+///
+/// Every element comes with the [`Rule`] that produced it and the tokens it consumed,
+/// and the rules that were tried without matching are listed as well. This is meant for
+/// triaging bad parses, use [`parse_with_options`] otherwise.
+pub fn parse_explained(input: &str, options: Options) -> Explanation<'_> {
+    Parser::new(options).explain(input)
+}
+
+/// Parses a string into up to `limit` candidate interpretations with the given options, ranked
+/// from best to worst.
+///
+/// Names such as `86 - 01` are inherently ambiguous, so the parser is re-run with the ambiguous
+/// decisions flipped, e.g. a number that was picked as the episode being part of the title, a
+/// number in the title being the episode instead, a number being a year or an ambiguous keyword
+/// being treated as plain text. Candidates are scored by how much of the input they explain and
+/// how confidently, so the first one is usually the same as the result of
+/// [`parse_with_options`].
+pub fn parse_candidates_with_options(
+    input: &str,
+    options: Options,
+    limit: usize,
+) -> Vec<Candidate<'_>> {
+    Parser::new(options).candidates(input, limit)
+}
+
+/// Parses a string into up to `limit` candidate interpretations with the default options,
+/// see [`parse_candidates_with_options`].
+pub fn parse_candidates(input: &str, limit: usize) -> Vec<Candidate<'_>> {
+    parse_candidates_with_options(input, Options::default(), limit)
+}
+
+/// Parses a path such as `Show Name (2019)/Season 2/05 - Episode Title.mkv` with the given options.
+///
+/// Every component of the path is parsed on its own and the results are merged, where the
+/// file name takes precedence:
+///
+/// - The title comes from the file name, unless it has none or it comes after the episode
+///   (in which case it is the episode title). It then comes from the nearest directory that
+///   has one, and a file name without an episode is parsed again as if it came after it.
+///   Without such a directory, a file name such as `05.mkv` in a directory such as `Specials`
+///   is parsed as if it came after the type, which makes it only an episode.
+/// - Every other kind that the file name lacks is taken from the nearest directory that has
+///   it, e.g. the season from `Season 2` or the year and release group of the series. Kinds
+///   that only describe a single file, such as the episode or the checksum, are never taken
+///   from a directory.
+///
+/// The spans of the elements are relative to the whole path and every element records the
+/// component that it came from.
+pub fn parse_path_with_options(path: &str, options: Options) -> ParsedPath<'_> {
+    Parser::new(options).parse_path(path)
+}
+
+/// Parses a path with the default options, see [`parse_path_with_options`].
+pub fn parse_path(path: &str) -> ParsedPath<'_> {
+    parse_path_with_options(path, Options::default())
+}
+
+/// Parses the names of a batch, such as the files of a folder, with the given options.
+///
+/// Names are often ambiguous on their own but rarely across a whole batch: in a folder of
+/// `[Group] Title - 01.mkv` through `- 24`, the common part is the title and the varying number
+/// is the episode. Every name is parsed into its candidates (see [`parse_candidates_with_options`]) and the
+/// one that agrees most with the rest of the batch is picked:
+///
+/// - The title and release group that more than half of the names agree on are preferred, which
+///   fixes e.g. numeric titles or a number that was mistaken for part of the title.
+/// - A number that varies between the names is preferred as the episode over the year.
+/// - A title such as `Group Title` is split when both the release group and the title match
+///   the rest of the batch, where the new elements have the [`Rule::BatchConsensus`] rule.
+///
+/// Ties are won by the better ranked candidate, so names that agree with the batch are parsed
+/// as they would be on their own. Every entry records the kinds that were corrected.
+pub fn parse_batch_with_options<'a>(inputs: &[&'a str], options: Options) -> Vec<BatchEntry<'a>> {
+    Parser::new(options).parse_batch(inputs)
+}
+
+/// Parses the names of a batch with the default options, see [`parse_batch_with_options`].
+pub fn parse_batch<'a>(inputs: &[&'a str]) -> Vec<BatchEntry<'a>> {
+    parse_batch_with_options(inputs, Options::default())
+}
+
+/// Parses a string into a typed [`ParsedRelease`] with the given options.
+///
+/// This runs the same pipeline as [`parse_with_options`], so the typed view always agrees
+/// with the [`Element`] objects returned by it.
+pub fn parse_release_with_options(input: &str, options: Options) -> ParsedRelease<'_> {
+    parse_with_options(input, options).into_iter().collect()
+}
+
+/// Parses a string into a typed [`ParsedRelease`] with the default options.
+pub fn parse_release(input: &str) -> ParsedRelease<'_> {
+    parse_release_with_options(input, Options::default())
+}
+
 #[cfg(feature = "wasm")]
 #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = parse))]
 pub fn parse_wasm(input: &str, options: options::JsOptions) -> Vec<element::JsElement> {
     parse_with_options(input, options.into())
         .into_iter()
-        .map(element::JsElement::from)
+        .map(|element| element::JsElement::new(input, element))
         .collect()
 }
@@ -6,7 +6,7 @@ use wasm_bindgen::prelude::*;
 pub struct Options(u16);
 
 impl Default for Options {
-    /// The default option is to have everything enabled
+    /// The default option is to have everything enabled except for unknown tokens, scene and torrent mode
     fn default() -> Self {
         Self(0b0000_0011_1111_1111)
     }
@@ -23,6 +23,9 @@ impl Options {
     const VIDEO_RESOLUTION: u16 = 1 << 7;
     const YEAR: u16 = 1 << 8;
     const DATE: u16 = 1 << 9;
+    const UNKNOWN: u16 = 1 << 10;
+    const SCENE: u16 = 1 << 11;
+    const TORRENT: u16 = 1 << 12;
 
     #[inline]
     const fn has_flag(&self, val: u16) -> bool {
@@ -90,6 +93,30 @@ impl Options {
         self.has_flag(Self::DATE)
     }
 
+    /// Returns a bool indiciating whether to report tokens that no parser claimed.
+    ///
+    /// These are reported as [`crate::ElementKind::Unknown`] elements.
+    pub const fn parse_unknown_tokens(&self) -> bool {
+        self.has_flag(Self::UNKNOWN)
+    }
+
+    /// Returns a bool indicating whether every name is parsed as a scene release.
+    ///
+    /// Names such as `Title.S01E02.1080p.WEB-DL.H.264-GROUP` are detected regardless, this
+    /// forces the conventions of scene releases onto names that lack e.g. the `-GROUP` suffix.
+    pub const fn parse_scene(&self) -> bool {
+        self.has_flag(Self::SCENE)
+    }
+
+    /// Returns a bool indicating whether names are parsed as the titles of torrents.
+    ///
+    /// Titles from indexer feeds such as `[Group] Title / Alt Title - 01 ~ 12 [1080p] | 1.4 GiB`
+    /// have no file extension, separate alternative titles with `/`, `|` or `+` and may end
+    /// with the size of the torrent. A range of episodes doesn't get an episode title.
+    pub const fn parse_torrent(&self) -> bool {
+        self.has_flag(Self::TORRENT)
+    }
+
     /// A builder method to toggle the option to parse episodes.
     pub fn episodes(mut self, toggle: bool) -> Self {
         self.toggle_flag(Self::EPISODE, toggle);
@@ -149,9 +176,28 @@ impl Options {
         self.toggle_flag(Self::DATE, toggle);
         self
     }
+
+    /// A builder method to toggle the option to report unknown tokens.
+    pub fn unknown_tokens(mut self, toggle: bool) -> Self {
+        self.toggle_flag(Self::UNKNOWN, toggle);
+        self
+    }
+
+    /// A builder method to toggle the option to parse every name as a scene release.
+    pub fn scene(mut self, toggle: bool) -> Self {
+        self.toggle_flag(Self::SCENE, toggle);
+        self
+    }
+
+    /// A builder method to toggle the option to parse names as the titles of torrents.
+    pub fn torrent(mut self, toggle: bool) -> Self {
+        self.toggle_flag(Self::TORRENT, toggle);
+        self
+    }
 }
 
 #[cfg(feature = "wasm")]
+#[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = Options))]
 pub struct JsOptions {
     pub episode: bool,
@@ -164,6 +210,9 @@ pub struct JsOptions {
     pub video_resolution: bool,
     pub year: bool,
     pub date: bool,
+    pub unknown: bool,
+    pub scene: bool,
+    pub torrent: bool,
 }
 
 #[cfg(feature = "wasm")]
@@ -182,6 +231,9 @@ impl JsOptions {
             video_resolution: true,
             year: true,
             date: true,
+            unknown: false,
+            scene: false,
+            torrent: false,
         }
     }
 }
@@ -206,5 +258,8 @@ impl From<JsOptions> for Options {
             .video_resolutions(value.video_resolution)
             .years(value.year)
             .dates(value.date)
+            .unknown_tokens(value.unknown)
+            .scene(value.scene)
+            .torrent(value.torrent)
     }
 }
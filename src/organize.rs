@@ -0,0 +1,825 @@
+//! Organizing a directory of media files into the layout of a media server.
+//!
+//! An [`Organizer`] walks a directory, parses the name of every file and plans where each
+//! of them should go. The resulting [`Plan`] can be inspected (or printed for a dry run)
+//! before it is applied. Applying a plan records every change in a journal, which [`undo`]
+//! uses to revert them.
+//!
+//! ```no_run
+//! use anitomy::{organize::{Action, Organizer}, Preset};
+//!
+//! let plan = Organizer::new(Preset::Jellyfin.into())
+//!     .action(Action::Hardlink)
+//!     .plan("Downloads".as_ref(), "Library".as_ref())?;
+//! print!("{plan}");
+//! if plan.conflicts.is_empty() {
+//!     plan.apply("Library/.anitomy-journal.tsv".as_ref())?;
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    element::{Element, ElementKind},
+    format::Template,
+    preset::{sanitize_component, Preset},
+    Options, Parser,
+};
+
+/// How a file is put at its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Moves the file, leaving nothing at the source
+    Move,
+    /// Creates a hard link, which requires the source and target to be on the same file system
+    Hardlink,
+    /// Creates a symbolic link to the absolute path of the source
+    Symlink,
+}
+
+impl Action {
+    /// Returns the name of the action as used in the journal, e.g. `hardlink`
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Action::Move => "move",
+            Action::Hardlink => "hardlink",
+            Action::Symlink => "symlink",
+        }
+    }
+
+    /// Returns the action with the given name, see [`Action::as_str`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Action::Move, Action::Hardlink, Action::Symlink]
+            .into_iter()
+            .find(|a| a.as_str() == name)
+    }
+
+    /// Puts the source at the target, which fails if something already exists there
+    fn perform(&self, source: &Path, target: &Path) -> io::Result<()> {
+        match self {
+            Action::Move => move_file(source, target),
+            Action::Hardlink => fs::hard_link(source, target),
+            Action::Symlink => symlink(&absolute(source)?, target),
+        }
+    }
+}
+
+/// Moves a file without overwriting anything at the target, unlike [`fs::rename`]
+fn move_file(source: &Path, target: &Path) -> io::Result<()> {
+    match fs::hard_link(source, target) {
+        Ok(()) => match fs::remove_file(source) {
+            Err(e) => {
+                let _ = fs::remove_file(target);
+                Err(e)
+            }
+            result => result,
+        },
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Err(e),
+        // Not every file system supports hard links, so check right before renaming instead
+        Err(_) => {
+            ensure_missing(target)?;
+            fs::rename(source, target)
+        }
+    }
+}
+
+/// Returns the absolute path without resolving symbolic links
+// FIXME: Use `std::path::absolute` when MSRV is bumped to 1.79 (currently 1.74)
+fn absolute(path: &Path) -> io::Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_owned())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
+
+fn ensure_missing(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("refusing to overwrite {}", path.display()),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(unix)]
+fn symlink(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+fn symlink(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(source, target)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink(_source: &Path, _target: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symbolic links are not supported on this platform",
+    ))
+}
+
+/// Where files go, relative to the target directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Layout {
+    /// The layout of a media server, see [`Preset::path`]
+    Preset(Preset),
+    /// A custom template, where every `/` in the rendered path starts a new directory
+    Template(Template),
+}
+
+impl Layout {
+    /// Returns the relative path of the parsed file, or `None` if it has no title
+    pub fn path(&self, elements: &[Element<'_>]) -> Option<PathBuf> {
+        match self {
+            Layout::Preset(preset) => preset.path(elements),
+            Layout::Template(template) => {
+                if !elements.iter().any(|e| e.kind == ElementKind::Title) {
+                    return None;
+                }
+                let path: PathBuf = template
+                    .render(elements)
+                    .split('/')
+                    .map(sanitize_component)
+                    .filter(|c| !c.is_empty())
+                    .collect();
+                (path.components().next().is_some()).then_some(path)
+            }
+        }
+    }
+}
+
+impl From<Preset> for Layout {
+    fn from(preset: Preset) -> Self {
+        Self::Preset(preset)
+    }
+}
+
+impl From<Template> for Layout {
+    fn from(template: Template) -> Self {
+        Self::Template(template)
+    }
+}
+
+/// A file that will be put at a new path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFile {
+    pub source: PathBuf,
+    pub target: PathBuf,
+}
+
+/// The files that belong to the same title.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// The title that is used for every file of the group
+    pub title: String,
+    pub files: Vec<PlannedFile>,
+}
+
+/// A target that can't be used, the files involved are left out of the plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// Multiple files map to the same target
+    Duplicate {
+        target: PathBuf,
+        sources: Vec<PathBuf>,
+    },
+    /// Something already exists at the target
+    Exists { source: PathBuf, target: PathBuf },
+}
+
+/// The changes that organizing a directory would make, see [`Organizer::plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub action: Action,
+    /// The files to organize, grouped by title and sorted by it
+    pub groups: Vec<Group>,
+    pub conflicts: Vec<Conflict>,
+    /// Files that aren't media files, have no title or are already at their target
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Plans how the files of a directory are organized.
+#[derive(Debug, Clone)]
+pub struct Organizer {
+    layout: Layout,
+    action: Action,
+    options: Options,
+}
+
+impl Organizer {
+    /// Creates an organizer that moves files into the given layout
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            action: Action::Move,
+            options: Options::default(),
+        }
+    }
+
+    /// Sets how files are put at their target
+    pub fn action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Sets the options used to parse the file names
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Walks the `source` directory and plans where every file goes inside `target`.
+    ///
+    /// Only files with a file extension and a title are considered, symbolic links to
+    /// directories aren't followed and the target is skipped if it is inside the source.
+    /// Files whose titles only differ in case and punctuation, e.g. `Show.Name` and
+    /// `Show Name`, are grouped under the most common spelling. Nothing is changed on disk.
+    pub fn plan(&self, source: &Path, target: &Path) -> io::Result<Plan> {
+        let mut files = Vec::new();
+        let exclude = fs::canonicalize(target).ok();
+        walk(source, exclude.as_deref(), &mut files)?;
+        files.sort();
+
+        let mut parser = Parser::new(self.options);
+        let mut skipped = Vec::new();
+        let mut parsed: Vec<(PathBuf, Vec<Element<'static>>)> = Vec::new();
+        for file in files {
+            let Some(name) = file.file_name().and_then(|n| n.to_str()) else {
+                skipped.push(file);
+                continue;
+            };
+            let elements: Vec<_> = parser
+                .parse(name)
+                .into_iter()
+                .map(|e| Element {
+                    kind: e.kind,
+                    value: Cow::Owned(e.value.into_owned()),
+                    position: e.position,
+                    span: e.span,
+                    rule: e.rule,
+                })
+                .collect();
+            let has = |kind| elements.iter().any(|e: &Element| e.kind == kind);
+            if has(ElementKind::Title) && has(ElementKind::FileExtension) {
+                parsed.push((file, elements));
+            } else {
+                skipped.push(file);
+            }
+        }
+
+        // The spellings of every title and how often they were used, in order of appearance
+        let mut spellings: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+        for (_, elements) in &parsed {
+            let title = title_of(elements);
+            let counts = spellings.entry(normalize_title(title)).or_default();
+            match counts.iter_mut().find(|(spelling, _)| spelling == title) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((title.to_owned(), 1)),
+            }
+        }
+
+        let mut groups: BTreeMap<String, Vec<PlannedFile>> = BTreeMap::new();
+        for (file, mut elements) in parsed {
+            let counts = &spellings[&normalize_title(title_of(&elements))];
+            // `max_by_key` returns the last maximum, so reverse to prefer the first spelling
+            let (title, _) = counts.iter().rev().max_by_key(|(_, count)| *count).unwrap();
+            for element in elements.iter_mut().filter(|e| e.kind == ElementKind::Title) {
+                element.value = Cow::Owned(title.clone());
+            }
+            match self.layout.path(&elements) {
+                Some(path) => groups.entry(title.clone()).or_default().push(PlannedFile {
+                    source: file,
+                    target: target.join(path),
+                }),
+                None => skipped.push(file),
+            }
+        }
+
+        let mut sources_by_target: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+        for file in groups.values().flatten() {
+            sources_by_target
+                .entry(file.target.clone())
+                .or_default()
+                .push(file.source.clone());
+        }
+
+        let mut conflicts = Vec::new();
+        let mut plan_groups = Vec::new();
+        for (title, files) in groups {
+            let mut planned = Vec::new();
+            for file in files {
+                let sources = &sources_by_target[&file.target];
+                if sources.len() > 1 {
+                    if sources[0] == file.source {
+                        conflicts.push(Conflict::Duplicate {
+                            target: file.target,
+                            sources: sources.clone(),
+                        });
+                    }
+                } else if is_same_file(&file.source, &file.target) {
+                    skipped.push(file.source);
+                } else if fs::symlink_metadata(&file.target).is_ok() {
+                    conflicts.push(Conflict::Exists {
+                        source: file.source,
+                        target: file.target,
+                    });
+                } else {
+                    planned.push(file);
+                }
+            }
+            if !planned.is_empty() {
+                plan_groups.push(Group {
+                    title,
+                    files: planned,
+                });
+            }
+        }
+        skipped.sort();
+
+        Ok(Plan {
+            action: self.action,
+            groups: plan_groups,
+            conflicts,
+            skipped,
+        })
+    }
+}
+
+fn walk(dir: &Path, exclude: Option<&Path>, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if exclude.is_some() && fs::canonicalize(&path).ok().as_deref() == exclude {
+                continue;
+            }
+            walk(&path, exclude, files)?;
+        } else if file_type.is_file() || (file_type.is_symlink() && path.is_file()) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn title_of<'a>(elements: &'a [Element<'_>]) -> &'a str {
+    elements
+        .iter()
+        .find(|e| e.kind == ElementKind::Title)
+        .map(|e| e.value())
+        .unwrap_or_default()
+}
+
+/// Lowercases a title and collapses everything that isn't alphanumeric into single spaces
+fn normalize_title(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+impl Plan {
+    /// Returns an iterator over every file of the plan
+    pub fn files(&self) -> impl Iterator<Item = &PlannedFile> {
+        self.groups.iter().flat_map(|g| g.files.iter())
+    }
+
+    /// Applies the plan, recording every change in the journal at the given path.
+    ///
+    /// The journal must not exist yet, so that undoing it reverts exactly this plan. Every change
+    /// is written as soon as it is made, so even a plan that failed halfway can be undone. Conflicting
+    /// files are not part of the plan and are left alone. Existing files are never
+    /// overwritten, even if they were created after planning, applying stops with an error
+    /// instead. Returns the number of files that were organized.
+    pub fn apply(&self, journal: &Path) -> io::Result<usize> {
+        // Checked up front so that a plan is never only partially journaled
+        for file in self.files() {
+            journal_path(&file.source)?;
+            journal_path(&file.target)?;
+            ensure_missing(&file.target)?;
+        }
+
+        let mut journal = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(journal)?;
+        let mut count = 0;
+        for file in self.files() {
+            if let Some(parent) = file.target.parent() {
+                let missing: Vec<_> = parent
+                    .ancestors()
+                    .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+                    .collect();
+                for dir in missing.into_iter().rev() {
+                    fs::create_dir(dir)?;
+                    write_entry(&mut journal, &["mkdir", journal_path(dir)?])?;
+                }
+            }
+            ensure_missing(&file.target)?;
+            self.action.perform(&file.source, &file.target)?;
+            write_entry(
+                &mut journal,
+                &[
+                    self.action.as_str(),
+                    journal_path(&file.source)?,
+                    journal_path(&file.target)?,
+                ],
+            )?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+impl fmt::Display for Plan {
+    /// Writes a human readable summary of the plan, as used for dry runs
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for group in &self.groups {
+            writeln!(f, "{}", group.title)?;
+            for file in &group.files {
+                writeln!(
+                    f,
+                    "  {} {} -> {}",
+                    self.action.as_str(),
+                    file.source.display(),
+                    file.target.display()
+                )?;
+            }
+        }
+        for conflict in &self.conflicts {
+            match conflict {
+                Conflict::Duplicate { target, sources } => {
+                    writeln!(
+                        f,
+                        "conflict: {} files map to {}",
+                        sources.len(),
+                        target.display()
+                    )?;
+                    for source in sources {
+                        writeln!(f, "  {}", source.display())?;
+                    }
+                }
+                Conflict::Exists { source, target } => writeln!(
+                    f,
+                    "conflict: {} already exists for {}",
+                    target.display(),
+                    source.display()
+                )?,
+            }
+        }
+        if !self.skipped.is_empty() {
+            writeln!(f, "skipped {} files", self.skipped.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a path as it is written into the journal, which is a tab separated file
+fn journal_path(path: &Path) -> io::Result<&str> {
+    path.to_str()
+        .filter(|s| !s.contains(['\t', '\n', '\r']))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("path can't be journaled: {}", path.display()),
+            )
+        })
+}
+
+fn write_entry(journal: &mut fs::File, fields: &[&str]) -> io::Result<()> {
+    // Written at once so that an interrupted write doesn't leave half an entry behind
+    journal.write_all(format!("{}\n", fields.join("\t")).as_bytes())?;
+    journal.flush()
+}
+
+/// The result of [`undo`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Undone {
+    /// The number of files that were reverted
+    pub reverted: usize,
+    /// The links that were left alone because they no longer refer to their source, e.g.
+    /// because the source was deleted or another file was put at the target
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Returns whether the target is still a hard link to the source
+#[cfg(unix)]
+fn is_hard_link(source: &Path, target: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let (source, target) = (fs::metadata(source)?, fs::symlink_metadata(target)?);
+    Ok(source.dev() == target.dev() && source.ino() == target.ino())
+}
+
+/// Returns whether the target is still a hard link to the source, which can only be told
+/// apart from a copy by its size and modification time here
+#[cfg(not(unix))]
+fn is_hard_link(source: &Path, target: &Path) -> io::Result<bool> {
+    let (source, target) = (fs::metadata(source)?, fs::symlink_metadata(target)?);
+    Ok(target.is_file()
+        && source.len() == target.len()
+        && source.modified()? == target.modified()?)
+}
+
+/// Returns whether the link at the target was created by the action and still refers to the
+/// source, so that removing it can't lose any data
+fn is_own_link(action: Action, source: &Path, target: &Path) -> io::Result<bool> {
+    let result = match action {
+        Action::Move => Ok(false),
+        Action::Hardlink => is_hard_link(source, target),
+        Action::Symlink => fs::read_link(target)
+            .map(|link| link == source || absolute(source).is_ok_and(|source| link == source)),
+    };
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        result => result,
+    }
+}
+
+/// Reverts every change recorded in a journal written by [`Plan::apply`], newest first.
+///
+/// Moved files are moved back, links are removed and created directories are removed if
+/// they are empty. A file that already exists at the original path of a moved file is never
+/// overwritten, undoing stops with an error instead. A link is only removed while it still
+/// refers to its source, otherwise it is the last copy of the file (e.g. the download was
+/// deleted after hard linking it) or a different file, so it is skipped and reported.
+///
+/// Every reverted change is removed from the journal right away, so an undo that failed
+/// halfway can be resumed once the error is resolved. The journal is removed at the end.
+pub fn undo(journal: &Path) -> io::Result<Undone> {
+    let mut lines = BufReader::new(fs::File::open(journal)?)
+        .lines()
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut undone = Undone::default();
+    while let Some(line) = lines.pop() {
+        let fields: Vec<_> = line.split('\t').collect();
+        match fields[..] {
+            [] | [""] => continue,
+            ["mkdir", dir] => {
+                // This fails for directories that are still used by other files, which are kept
+                let _ = fs::remove_dir(dir);
+            }
+            [action, source, target] => {
+                let (source, target) = (Path::new(source), Path::new(target));
+                let reverted = match Action::from_name(action) {
+                    // A move that was already reverted by an interrupted undo is done
+                    Some(Action::Move) if !target.exists() && source.exists() => true,
+                    Some(Action::Move) => {
+                        if let Some(parent) = source.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        move_file(target, source)?;
+                        true
+                    }
+                    // A link that was already removed by an interrupted undo is done
+                    Some(Action::Hardlink | Action::Symlink)
+                        if fs::symlink_metadata(target).is_err() =>
+                    {
+                        true
+                    }
+                    Some(action) => {
+                        let is_own_link = is_own_link(action, source, target)?;
+                        if is_own_link {
+                            fs::remove_file(target)?;
+                        } else {
+                            undone.skipped.push(target.to_owned());
+                        }
+                        is_own_link
+                    }
+                    None => return Err(invalid_entry(&line)),
+                };
+                if reverted {
+                    undone.reverted += 1;
+                }
+            }
+            _ => return Err(invalid_entry(&line)),
+        }
+        let mut remaining = lines.join("\n");
+        if !remaining.is_empty() {
+            remaining.push('\n');
+        }
+        fs::write(journal, remaining)?;
+    }
+    fs::remove_file(journal)?;
+    undone.skipped.reverse();
+    Ok(undone)
+}
+
+fn invalid_entry(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid journal entry: {line:?}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("anitomy-organize-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("Show.Name!"), "show name");
+        assert_eq!(normalize_title("SHOW - NAME"), "show name");
+        assert_eq!(normalize_title("Fate/Zero"), "fate zero");
+    }
+
+    #[test]
+    fn test_plan_apply_and_undo() {
+        let temp = TempDir::new("apply");
+        let source = temp.0.join("downloads");
+        let target = temp.0.join("library");
+        touch(&source.join("[Group] Show Name - 01 [1080p].mkv"));
+        touch(&source.join("nested/[Other] Show.Name - 02 [720p].mkv"));
+        touch(&source.join("[Group] Show Name - 03 [1080p].mkv"));
+        touch(&source.join("[Third] SHOW NAME - 04 [1080p].mkv"));
+        touch(&source.join("notes.txt"));
+
+        let plan = Organizer::new(Preset::Plex.into())
+            .plan(&source, &target)
+            .unwrap();
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.skipped, [source.join("notes.txt")]);
+        assert_eq!(plan.groups.len(), 1);
+        assert_eq!(plan.groups[0].title, "Show Name");
+        let targets: Vec<_> = plan.files().map(|f| f.target.clone()).collect();
+        let season = target.join("Show Name/Season 01");
+        assert_eq!(
+            targets,
+            [
+                season.join("Show Name - s01e01.mkv"),
+                season.join("Show Name - s01e03.mkv"),
+                season.join("Show Name - s01e04.mkv"),
+                season.join("Show Name - s01e02.mkv"),
+            ]
+        );
+        assert!(plan.to_string().contains("move "));
+
+        let journal = temp.0.join("journal.tsv");
+        assert_eq!(plan.apply(&journal).unwrap(), 4);
+        assert!(targets.iter().all(|t| t.is_file()));
+        assert!(!source
+            .join("nested/[Other] Show.Name - 02 [720p].mkv")
+            .exists());
+
+        // Organizing the library again doesn't do anything
+        let again = Organizer::new(Preset::Plex.into())
+            .plan(&target, &target)
+            .unwrap();
+        assert!(again.groups.is_empty() && again.conflicts.is_empty());
+
+        assert_eq!(undo(&journal).unwrap().reverted, 4);
+        assert!(source
+            .join("nested/[Other] Show.Name - 02 [720p].mkv")
+            .is_file());
+        assert!(!target.exists());
+        assert!(!journal.exists());
+    }
+
+    #[test]
+    fn test_never_overwrites() {
+        let temp = TempDir::new("overwrite");
+        let source = temp.0.join("downloads");
+        let target = temp.0.join("library");
+        let first = source.join("[Group] Title - 01.mkv");
+        let second = source.join("[Group] Title - 02.mkv");
+        touch(&first);
+        touch(&second);
+
+        // A file that shows up at a target after planning is kept
+        let plan = Organizer::new(Preset::Plex.into())
+            .plan(&source, &target)
+            .unwrap();
+        let targets: Vec<_> = plan.files().map(|f| f.target.clone()).collect();
+        touch(&targets[1]);
+        fs::write(&targets[1], "existing").unwrap();
+        let journal = temp.0.join("journal.tsv");
+        let error = plan.apply(&journal).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&targets[1]).unwrap(), "existing");
+        assert!(first.is_file() && second.is_file());
+        fs::remove_file(&targets[1]).unwrap();
+
+        // Undoing doesn't overwrite a new file at the source and can be resumed afterwards
+        assert_eq!(plan.apply(&journal).unwrap(), 2);
+        fs::write(&first, "new").unwrap();
+        let error = undo(&journal).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "new");
+        assert!(second.is_file() && targets[0].is_file() && !targets[1].exists());
+        fs::remove_file(&first).unwrap();
+        assert_eq!(undo(&journal).unwrap().reverted, 1);
+        assert!(first.is_file() && !targets[0].exists() && !journal.exists());
+    }
+
+    #[test]
+    fn test_undo_keeps_unlinked_files() {
+        let temp = TempDir::new("unlinked");
+        let source = temp.0.join("downloads");
+        let target = temp.0.join("library");
+        let first = source.join("[Group] Title - 01.mkv");
+        let second = source.join("[Group] Title - 02.mkv");
+        touch(&first);
+        touch(&second);
+
+        let plan = Organizer::new(Preset::Plex.into())
+            .action(Action::Hardlink)
+            .plan(&source, &target)
+            .unwrap();
+        let targets: Vec<_> = plan.files().map(|f| f.target.clone()).collect();
+        let journal = temp.0.join("journal.tsv");
+        assert_eq!(plan.apply(&journal).unwrap(), 2);
+        assert_eq!(
+            plan.apply(&journal).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+
+        // The link is the last copy once the download is deleted
+        fs::remove_file(&first).unwrap();
+        fs::remove_file(&targets[1]).unwrap();
+        fs::write(&targets[1], "replaced").unwrap();
+        let undone = undo(&journal).unwrap();
+        assert_eq!(undone.reverted, 0);
+        assert_eq!(undone.skipped, targets);
+        assert!(targets[0].is_file());
+        assert_eq!(fs::read_to_string(&targets[1]).unwrap(), "replaced");
+        assert!(second.is_file() && !journal.exists());
+    }
+
+    #[test]
+    fn test_plan_conflicts() {
+        let temp = TempDir::new("conflicts");
+        let source = temp.0.join("downloads");
+        let target = temp.0.join("library");
+        touch(&source.join("[A] Title - 01 [1080p].mkv"));
+        touch(&source.join("[B] Title - 01 [720p].mkv"));
+        touch(&source.join("[A] Title - 02 [1080p].mkv"));
+        touch(&target.join("Title/Season 01/Title S01E02.mkv"));
+
+        let plan = Organizer::new(Preset::Jellyfin.into())
+            .action(Action::Hardlink)
+            .plan(&source, &target)
+            .unwrap();
+        assert!(plan.groups.is_empty());
+        assert_eq!(
+            plan.conflicts,
+            [
+                Conflict::Duplicate {
+                    target: target.join("Title/Season 01/Title S01E01.mkv"),
+                    sources: vec![
+                        source.join("[A] Title - 01 [1080p].mkv"),
+                        source.join("[B] Title - 01 [720p].mkv"),
+                    ],
+                },
+                Conflict::Exists {
+                    source: source.join("[A] Title - 02 [1080p].mkv"),
+                    target: target.join("Title/Season 01/Title S01E02.mkv"),
+                },
+            ]
+        );
+    }
+}
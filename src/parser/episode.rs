@@ -1,6 +1,7 @@
 use crate::{
     element::{Element, ElementKind},
     keyword::KeywordKind,
+    rule::Rule,
     tokenizer::{is_dash, Token},
     utils::*,
 };
@@ -13,6 +14,16 @@ fn episode_prefix_regex() -> &'static Regex {
         .get_or_init(|| Regex::new(r#"^(?i:(?:E|EP|Eps)(\d{1,4}(?:\.5)?)(?:[vV](\d))?$)"#).unwrap())
 }
 
+fn episode_prefix_range_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r#"^(?i:(?:E|EP|Eps)(\d{1,4})(?:[vV](\d))?[-~](?:E|EP|Eps)?(\d{1,4})(?:[vV](\d))?)$"#,
+        )
+        .unwrap()
+    })
+}
+
 fn season_and_episode_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| Regex::new(r#"^(?i:S?(\d{1,2})(?:-S?(\d{1,2}))?(?:x|[ ._-x]?EP?)(\d{1,4})(?:-(?:EP?)?(\d{1,4}))?(?:[vV](\d))?$)"#).unwrap())
@@ -23,7 +34,33 @@ fn number_sign_episode_regex() -> &'static Regex {
     REGEX.get_or_init(|| Regex::new(r#"[#＃](\d{1,4})(?:[-~&+](\d{1,4}))?(?:[vV](\d))?"#).unwrap())
 }
 
-fn parse_number_in_number_episode<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
+fn number_of_total_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"^(\d{1,4})/(\d{1,4})$"#).unwrap())
+}
+
+/// Splits a number and the total number, e.g. `03/12`, if the number is within the total
+pub fn parse_number_of_total(s: &str) -> Option<(&str, &str)> {
+    let captures = number_of_total_regex().captures(s)?;
+    let (number, total) = (captures.get(1)?.as_str(), captures.get(2)?.as_str());
+    let is_within = number.parse::<u16>().ok()? <= total.parse::<u16>().ok()?;
+    is_within.then_some((number, total))
+}
+
+/// Returns the index of the total number after a number, e.g. `12` in `3 of 12`
+pub fn find_total_of(tokens: &[Token<'_>], index: usize) -> Option<usize> {
+    let middle = find_next_token(tokens, index, true, |t| t.is_not_delimiter())?;
+    if !(tokens[middle].is_free() && tokens[middle].value.eq_ignore_ascii_case("of")) {
+        return None;
+    }
+    let total = find_next_token(tokens, middle, true, |t| t.is_not_delimiter())?;
+    (tokens[total].is_free() && tokens[total].is_number()).then_some(total)
+}
+
+fn parse_number_in_number_episode<'a>(
+    tokens: &mut [Token<'a>],
+    results: &mut Vec<Element<'a>>,
+) -> bool {
     for index in 0..tokens.len() {
         {
             let token = &tokens[index];
@@ -40,30 +77,58 @@ fn parse_number_in_number_episode<'a>(tokens: &mut [Token<'a>]) -> Option<Elemen
         if separator_value != "&" && separator_value != "~" && separator_value != "of" {
             continue;
         }
-        if index + 1 <= middle
+        if index < middle
             && tokens[index + 1..middle]
                 .iter()
                 .any(|t| t.is_not_delimiter())
         {
             continue;
         }
-        if let Some(other_number) = tokens[middle..]
-            .iter_mut()
-            .skip(1)
-            .find(|t| t.is_not_delimiter())
-        {
-            if !other_number.is_number() {
-                continue;
-            }
-            if separator_value != "of" {
-                other_number.mark_known();
-            }
-            tokens[middle].mark_known();
-            tokens[index].mark_known();
-            return Some(Element::new(ElementKind::Episode, &tokens[index]));
+        let Some(other) = find_next_token(tokens, middle, true, |t| t.is_not_delimiter()) else {
+            continue;
+        };
+        if !tokens[other].is_number() {
+            continue;
+        }
+        tokens[other].mark_known();
+        tokens[middle].mark_known();
+        tokens[index].mark_known();
+        results.push(Element::new(
+            ElementKind::Episode,
+            Rule::NumberInNumber,
+            &tokens[index],
+        ));
+        // The number after `of` is the total number of episodes, e.g. `3 of 12`
+        if separator_value == "of" {
+            results.push(Element::new(
+                ElementKind::EpisodeTotal,
+                Rule::NumberInNumber,
+                &tokens[other],
+            ));
+        }
+        return true;
+    }
+    false
+}
+
+/// Parses the Japanese count of every episode or volume, e.g. `全12話` or `全5巻`
+pub fn parse_japanese_total<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>) {
+    for token in tokens.iter_mut().filter(|t| t.is_free()) {
+        let Some(rest) = token.value.strip_prefix('全') else {
+            continue;
+        };
+        let (total, kind) = if let Some(total) = rest.strip_suffix('話') {
+            (total, ElementKind::EpisodeTotal)
+        } else if let Some(total) = rest.strip_suffix('巻') {
+            (total, ElementKind::VolumeTotal)
+        } else {
+            continue;
+        };
+        if super::common::is_valid_japanese_episode(total) {
+            token.mark_known();
+            results.push(Element::with_value(kind, Rule::TotalJapanese, token, total));
         }
     }
-    None
 }
 
 pub fn parse_single_episode(s: &str) -> Option<(&str, &str)> {
@@ -83,49 +148,51 @@ pub fn parse_single_episode(s: &str) -> Option<(&str, &str)> {
     }
 }
 
+/// Pushes the two ends of a range of episodes along with their versions, e.g. `01v2-12v3`,
+/// if the range is in order
+fn push_episode_range<'a>(
+    results: &mut Vec<Element<'a>>,
+    kind: ElementKind,
+    rule: Rule,
+    token: &Token<'a>,
+    (lower, low_version): (&'a str, &'a str),
+    (upper, up_version): (&'a str, &'a str),
+) -> bool {
+    match lower.parse::<u16>().ok().zip(upper.parse::<u16>().ok()) {
+        Some((x, y)) if x < y => {}
+        _ => return false,
+    }
+    for (episode, version) in [(lower, low_version), (upper, up_version)] {
+        results.push(Element::with_value(kind, rule, token, episode));
+        if !version.is_empty() {
+            results.push(Element::with_value(
+                ElementKind::ReleaseVersion,
+                rule,
+                token,
+                version,
+            ));
+        }
+    }
+    true
+}
+
 pub fn parse_multi_episode_range<'a>(
     tokens: &mut [Token<'a>],
     index: usize,
     results: &mut Vec<Element<'a>>,
     kind: ElementKind,
+    rule: Rule,
 ) -> bool {
-    if let Some((first, last)) = tokens[index].value.split_once(['-', '~', '&', '+']) {
-        let token = &mut tokens[index];
-        if let Some(((lower, low_version), (upper, up_version))) =
-            parse_single_episode(first).zip(parse_single_episode(last))
-        {
-            match lower.parse::<u16>().ok().zip(upper.parse::<u16>().ok()) {
-                Some((x, y)) if x < y => {
-                    results.push(Element {
-                        kind,
-                        value: lower.into(),
-                        position: token.position,
-                    });
-                    token.mark_known();
-                    if !low_version.is_empty() {
-                        results.push(Element {
-                            kind: ElementKind::ReleaseVersion,
-                            value: low_version.into(),
-                            position: token.position,
-                        });
-                    }
-                    results.push(Element {
-                        kind,
-                        value: upper.into(),
-                        position: token.position,
-                    });
-                    if !up_version.is_empty() {
-                        results.push(Element {
-                            kind: ElementKind::ReleaseVersion,
-                            value: up_version.into(),
-                            position: token.position,
-                        });
-                    }
-                    return true;
-                }
-                _ => {}
-            }
-        }
+    let token = &mut tokens[index];
+    let Some((first, last)) = token.value.split_once(['-', '~', '&', '+']) else {
+        return false;
+    };
+    let Some((lower, upper)) = parse_single_episode(first).zip(parse_single_episode(last)) else {
+        return false;
+    };
+    if push_episode_range(results, kind, rule, token, lower, upper) {
+        token.mark_known();
+        return true;
     }
     false
 }
@@ -148,7 +215,8 @@ pub fn parse_episode<'a>(
         if is_keyword {
             if let Some(next) = find_next_token(tokens, index, true, |t| t.is_not_delimiter()) {
                 if tokens[next].is_free() && tokens[next].is_mostly_numbers() {
-                    if parse_multi_episode_range(tokens, next, results, kind) {
+                    if parse_multi_episode_range(tokens, next, results, kind, Rule::EpisodeKeyword)
+                    {
                         tokens[index].mark_known();
                         return;
                     }
@@ -156,7 +224,7 @@ pub fn parse_episode<'a>(
                     if tokens[next].is_number() {
                         tokens[index].mark_known();
                         tokens[next].mark_known();
-                        results.push(Element::new(kind, &tokens[next]));
+                        results.push(Element::new(kind, Rule::EpisodeKeyword, &tokens[next]));
                         return;
                     }
                 }
@@ -165,26 +233,42 @@ pub fn parse_episode<'a>(
     }
 
     for token in tokens.iter_mut().filter(|t| t.is_free()) {
+        // A range with prefixes, e.g. `E01-E13`
+        if let Some(m) = episode_prefix_range_regex().captures(token.value) {
+            let group = |index| m.get(index).map_or("", |m| m.as_str());
+            if push_episode_range(
+                results,
+                kind,
+                Rule::EpisodePrefix,
+                token,
+                (group(1), group(2)),
+                (group(3), group(4)),
+            ) {
+                token.mark_known();
+                return;
+            }
+        }
         if let Some(m) = episode_prefix_regex().captures(token.value) {
-            results.push(Element {
+            results.push(Element::with_value(
                 kind,
-                value: m.get(1).unwrap().as_str().into(),
-                position: token.position,
-            });
+                Rule::EpisodePrefix,
+                token,
+                m.get(1).unwrap().as_str(),
+            ));
             token.mark_known();
             if let Some(inner) = m.get(2) {
-                results.push(Element {
-                    kind: ElementKind::ReleaseVersion,
-                    value: inner.as_str().into(),
-                    position: token.position,
-                });
+                results.push(Element::with_value(
+                    ElementKind::ReleaseVersion,
+                    Rule::EpisodePrefix,
+                    token,
+                    inner.as_str(),
+                ));
             }
             return;
         }
     }
 
-    if let Some(number) = parse_number_in_number_episode(tokens) {
-        results.push(number);
+    if parse_number_in_number_episode(tokens, results) {
         return;
     }
 
@@ -192,23 +276,27 @@ pub fn parse_episode<'a>(
         if let Some((prefix, suffix)) = parse_single_episode(token.value) {
             if !suffix.is_empty() {
                 token.mark_known();
-                results.push(Element {
+                results.push(Element::with_value(
                     kind,
-                    value: prefix.into(),
-                    position: token.position,
-                });
-                results.push(Element {
-                    kind: ElementKind::ReleaseVersion,
-                    value: suffix.into(),
-                    position: token.position,
-                });
+                    Rule::EpisodeVersion,
+                    token,
+                    prefix,
+                ));
+                results.push(Element::with_value(
+                    ElementKind::ReleaseVersion,
+                    Rule::EpisodeVersion,
+                    token,
+                    suffix,
+                ));
                 return;
             }
         }
     }
 
     for index in 0..tokens.len() {
-        if tokens[index].is_free() && parse_multi_episode_range(tokens, index, results, kind) {
+        if tokens[index].is_free()
+            && parse_multi_episode_range(tokens, index, results, kind, Rule::EpisodeRange)
+        {
             return;
         }
     }
@@ -216,38 +304,43 @@ pub fn parse_episode<'a>(
     for token in tokens.iter_mut().filter(|t| t.is_free()) {
         if let Some(captures) = season_and_episode_regex().captures(token.value) {
             if captures[1].parse::<u8>().unwrap_or_default() != 0 {
-                results.push(Element {
-                    kind: ElementKind::Season,
-                    value: captures.get(1).unwrap().as_str().into(),
-                    position: token.position,
-                });
+                results.push(Element::with_value(
+                    ElementKind::Season,
+                    Rule::SeasonAndEpisode,
+                    token,
+                    captures.get(1).unwrap().as_str(),
+                ));
                 token.mark_known();
                 if let Some(inner) = captures.get(2) {
-                    results.push(Element {
-                        kind: ElementKind::Season,
-                        value: inner.as_str().into(),
-                        position: token.position,
-                    });
+                    results.push(Element::with_value(
+                        ElementKind::Season,
+                        Rule::SeasonAndEpisode,
+                        token,
+                        inner.as_str(),
+                    ));
                 }
 
-                results.push(Element {
+                results.push(Element::with_value(
                     kind,
-                    value: captures.get(3).unwrap().as_str().into(),
-                    position: token.position,
-                });
+                    Rule::SeasonAndEpisode,
+                    token,
+                    captures.get(3).unwrap().as_str(),
+                ));
                 if let Some(inner) = captures.get(4) {
-                    results.push(Element {
+                    results.push(Element::with_value(
                         kind,
-                        value: inner.as_str().into(),
-                        position: token.position,
-                    });
+                        Rule::SeasonAndEpisode,
+                        token,
+                        inner.as_str(),
+                    ));
                 }
                 if let Some(inner) = captures.get(5) {
-                    results.push(Element {
-                        kind: ElementKind::ReleaseVersion,
-                        value: inner.as_str().into(),
-                        position: token.position,
-                    });
+                    results.push(Element::with_value(
+                        ElementKind::ReleaseVersion,
+                        Rule::SeasonAndEpisode,
+                        token,
+                        inner.as_str(),
+                    ));
                 }
                 return;
             }
@@ -264,7 +357,7 @@ pub fn parse_episode<'a>(
     ) {
         if token.is_free() && token.is_number() {
             token.mark_known();
-            results.push(Element::new(kind, token));
+            results.push(Element::new(kind, Rule::EpisodeAfterType, token));
             return;
         }
     }
@@ -272,24 +365,27 @@ pub fn parse_episode<'a>(
     for token in tokens.iter_mut().filter(|t| t.is_free()) {
         if let Some(captures) = number_sign_episode_regex().captures(token.value) {
             token.mark_known();
-            results.push(Element {
+            results.push(Element::with_value(
                 kind,
-                value: captures.get(1).unwrap().as_str().into(),
-                position: token.position,
-            });
+                Rule::NumberSign,
+                token,
+                captures.get(1).unwrap().as_str(),
+            ));
             if let Some(inner) = captures.get(2) {
-                results.push(Element {
+                results.push(Element::with_value(
                     kind,
-                    value: inner.as_str().into(),
-                    position: token.position,
-                });
+                    Rule::NumberSign,
+                    token,
+                    inner.as_str(),
+                ));
             }
             if let Some(inner) = captures.get(3) {
-                results.push(Element {
-                    kind: ElementKind::ReleaseVersion,
-                    value: inner.as_str().into(),
-                    position: token.position,
-                });
+                results.push(Element::with_value(
+                    ElementKind::ReleaseVersion,
+                    Rule::NumberSign,
+                    token,
+                    inner.as_str(),
+                ));
             }
             return;
         }
@@ -300,11 +396,12 @@ pub fn parse_episode<'a>(
             let prefix = prefix.strip_prefix('第').unwrap_or(prefix);
             if super::common::is_valid_japanese_episode(prefix) {
                 token.mark_known();
-                results.push(Element {
+                results.push(Element::with_value(
                     kind,
-                    value: prefix.into(),
-                    position: token.position,
-                });
+                    Rule::EpisodeJapanese,
+                    token,
+                    prefix,
+                ));
                 return;
             }
         }
@@ -360,8 +457,8 @@ pub fn parse_episode<'a>(
 
             tokens[next].mark_known();
             tokens[index].mark_known();
-            results.push(Element::new(b, &tokens[next]));
-            results.push(Element::new(a, &tokens[index]));
+            results.push(Element::new(b, Rule::EpisodeEquivalent, &tokens[next]));
+            results.push(Element::new(a, Rule::EpisodeEquivalent, &tokens[index]));
             return;
         }
     }
@@ -378,7 +475,7 @@ pub fn parse_episode<'a>(
         if let Some(token) = tokens.iter_mut().skip(index).find(|x| x.is_not_delimiter()) {
             if token.is_number() && token.is_free() {
                 token.mark_known();
-                results.push(Element::new(kind, token));
+                results.push(Element::new(kind, Rule::SeparatedNumber, token));
                 tokens[index].mark_known();
                 return;
             }
@@ -389,7 +486,7 @@ pub fn parse_episode<'a>(
         if let Some((first, second)) = token.value.split_once('.') {
             if second == "5" && super::common::is_valid_episode_number(first) {
                 token.mark_known();
-                results.push(Element::new(kind, token));
+                results.push(Element::new(kind, Rule::FractionalEpisode, token));
                 return;
             }
         }
@@ -403,7 +500,7 @@ pub fn parse_episode<'a>(
                 && middle.is_free()
                 && middle.is_number()
             {
-                results.push(Element::new(kind, middle));
+                results.push(Element::new(kind, Rule::EnclosedNumber, middle));
                 middle.mark_known();
                 return;
             }
@@ -433,10 +530,10 @@ pub fn parse_episode<'a>(
         .map(|(i, _)| i)
         .collect();
 
-    for i in partial_episode_indices {
+    if let Some(&i) = partial_episode_indices.first() {
         let token = &mut tokens[i];
         token.mark_known();
-        results.push(Element::new(kind, token));
+        results.push(Element::new(kind, Rule::PartialEpisode, token));
         return;
     }
 
@@ -494,7 +591,48 @@ pub fn parse_episode<'a>(
 
         let token = &mut tokens[index];
         token.mark_known();
-        results.push(Element::new(kind, token));
-        break;
+        results.push(Element::new(kind, Rule::LastNumber, token));
+        return;
+    }
+
+    // A number and the total such as `1/2` is often part of the title, e.g. `Ranma 1/2`, so it
+    // is only the episode when it stands apart from the title, e.g. `[03/12]`, `- 03/12` or
+    // `Episode 03/12`
+    for index in 0..tokens.len() {
+        if !tokens[index].is_free() {
+            continue;
+        }
+        let Some((number, total)) = parse_number_of_total(tokens[index].value) else {
+            continue;
+        };
+        let is_after_dash = tokens[..index]
+            .iter()
+            .rev()
+            .take_while(|t| t.is_delimiter())
+            .any(|t| t.value.chars().next().is_some_and(is_dash));
+        let is_after_keyword = find_prev_token(tokens, Some(index), |t| t.is_not_delimiter())
+            .is_some_and(|i| {
+                tokens[i]
+                    .keyword
+                    .is_some_and(|k| k.kind == KeywordKind::Episode)
+            });
+        if !(tokens[index].is_enclosed || is_after_dash || is_after_keyword) {
+            continue;
+        }
+        let token = &mut tokens[index];
+        token.mark_known();
+        results.push(Element::with_value(
+            kind,
+            Rule::NumberOfTotal,
+            token,
+            number,
+        ));
+        results.push(Element::with_value(
+            ElementKind::EpisodeTotal,
+            Rule::NumberOfTotal,
+            token,
+            total,
+        ));
+        return;
     }
 }
@@ -1,16 +1,19 @@
+use std::ops::Range;
+
 use crate::{
     element::{Element, ElementKind},
     keyword::KeywordKind,
+    rule::Rule,
     tokenizer::{combine_tokens, KeepDelimiters, Token},
     utils::*,
 };
 
-pub fn find_episode_title<'a, 'b>(tokens: &'b mut [Token<'a>]) -> Option<&'b mut [Token<'a>]> {
+pub fn find_episode_title(tokens: &[Token<'_>]) -> Option<Range<usize>> {
     let mut first = tokens.iter().position(|t| t.is_free() && !t.is_enclosed);
     let mut last = first.and_then(|index| {
         find_next_token(tokens, index, false, |t| {
             t.is_open_bracket()
-                || (t.is_identified() && t.keyword.map_or(false, |k| k.kind != KeywordKind::Part))
+                || (t.is_identified() && t.keyword.is_some_and(|k| k.kind != KeywordKind::Part))
         })
     });
 
@@ -38,26 +41,32 @@ pub fn find_episode_title<'a, 'b>(tokens: &'b mut [Token<'a>]) -> Option<&'b mut
     }
 
     match (first, last) {
-        (Some(x), Some(y)) => Some(&mut tokens[x..y]),
-        (Some(x), None) => Some(&mut tokens[x..]),
+        (Some(x), Some(y)) => Some(x..y),
+        (Some(x), None) => Some(x..tokens.len()),
         _ => None,
     }
 }
 
 pub fn parse_episode_title<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
-    let range = find_episode_title(tokens)?;
+    let Range { start, mut end } = find_episode_title(tokens)?;
+    // The `.` before the file extension is not part of the title, unlike e.g. the one in
+    // `All's well that ends well. (v3)`
+    let is_before_extension = tokens
+        .get(end)
+        .is_some_and(|t| t.is_identified() && t.keyword_kind() == Some(KeywordKind::FileExtension));
+    if is_before_extension && end > start && tokens[end - 1].is_delimiter() {
+        end -= 1;
+    }
+    let range = &mut tokens[start..end];
     let value = combine_tokens(range, KeepDelimiters::No);
     if value.is_empty() {
         None
     } else {
-        let position = range.first()?.position;
+        let element =
+            Element::from_tokens(ElementKind::EpisodeTitle, Rule::EpisodeTitle, range, value)?;
         for token in range {
             token.mark_known();
         }
-        Some(Element {
-            kind: ElementKind::EpisodeTitle,
-            value: value.into(),
-            position,
-        })
+        Some(element)
     }
 }
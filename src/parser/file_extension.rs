@@ -1,5 +1,6 @@
 use crate::{
     element::{Element, ElementKind},
+    rule::Rule,
     tokenizer::Token,
     utils::*,
 };
@@ -13,7 +14,11 @@ pub fn parse_file_extension<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>>
     if is_file_extension && is_dot {
         previous.mark_known();
         last.mark_known();
-        Some(Element::new(ElementKind::FileExtension, last))
+        Some(Element::new(
+            ElementKind::FileExtension,
+            Rule::FileExtension,
+            last,
+        ))
     } else {
         None
     }
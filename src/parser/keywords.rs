@@ -1,9 +1,9 @@
 use crate::{
     element::{Element, ElementKind},
     keyword::KeywordKind,
+    rule::Rule,
     tokenizer::Token,
 };
-use std::borrow::Cow;
 
 pub fn keyword_kind_to_element_kind(keyword: KeywordKind) -> Option<ElementKind> {
     match keyword {
@@ -52,18 +52,17 @@ pub fn parse_keywords<'a>(
             continue;
         };
 
-        if !keyword.is_ambiguous() || token.is_enclosed {
+        let rule = if !keyword.is_ambiguous() || token.is_enclosed {
             token.mark_known();
-        }
+            Rule::Keyword
+        } else {
+            Rule::AmbiguousKeyword
+        };
 
         let value = match keyword.kind {
             KeywordKind::ReleaseVersion => &token.value[1..],
             _ => token.value,
         };
-        results.push(Element {
-            kind: element_kind,
-            value: Cow::Borrowed(value),
-            position: token.position,
-        });
+        results.push(Element::with_value(element_kind, rule, token, value));
     }
 }
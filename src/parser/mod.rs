@@ -6,88 +6,318 @@ pub mod file_extension;
 pub mod keywords;
 pub mod part;
 pub mod release_group;
+pub mod scene;
 pub mod season;
 pub mod title;
+pub mod torrent;
+pub mod unknown;
 pub mod video_resolution;
 pub mod volume;
 pub mod year;
 
 use crate::{
+    batch::BatchEntry,
+    candidates::Candidate,
     element::{Element, ElementKind},
-    tokenizer::Token,
+    explain::{Explanation, Trace},
+    keyword::KeywordSet,
+    path::ParsedPath,
+    rule::Rule,
+    tokenizer::{Token, Tokenizer},
+    utils::recycle_vec,
     Options,
 };
 
-pub(crate) fn parse_with_options(mut tokens: Vec<Token<'_>>, options: Options) -> Vec<Element<'_>> {
-    let mut results = Vec::new();
-    if options.parse_file_extension() {
-        if let Some(el) = file_extension::parse_file_extension(&mut tokens) {
-            results.push(el);
+/// A reusable parser that holds its configuration along with scratch buffers.
+///
+/// When parsing many strings in a row this avoids allocating new buffers for every
+/// parse. The free functions such as [`crate::parse`] are wrappers over a default parser.
+#[derive(Debug, Clone, Default)]
+pub struct Parser {
+    options: Options,
+    keywords: KeywordSet,
+    tokens: Vec<Token<'static>>,
+}
+
+impl Parser {
+    /// Creates a new parser with the given options and the built-in keywords.
+    pub fn new(options: Options) -> Self {
+        Self {
+            options,
+            ..Self::default()
         }
     }
 
-    keywords::parse_keywords(&mut tokens, &options, &mut results);
+    /// A builder method to set the keywords used by the parser.
+    pub fn with_keywords(mut self, keywords: KeywordSet) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// Returns the options used by the parser.
+    pub fn options(&self) -> Options {
+        self.options
+    }
 
-    if options.parse_file_checksum() {
-        if let Some(el) = file_checksum::parse_file_checksum(&mut tokens) {
-            results.push(el);
+    /// Returns a mutable reference to the options used by the parser.
+    pub fn options_mut(&mut self) -> &mut Options {
+        &mut self.options
+    }
+
+    /// Returns the keywords used by the parser.
+    pub fn keywords(&self) -> &KeywordSet {
+        &self.keywords
+    }
+
+    /// Returns a mutable reference to the keywords used by the parser.
+    pub fn keywords_mut(&mut self) -> &mut KeywordSet {
+        &mut self.keywords
+    }
+
+    /// Splits a string into its tokens using the keywords of the parser.
+    ///
+    /// Concatenating the values of the returned tokens reproduces the input exactly.
+    pub fn tokenize<'a>(&self, input: &'a str) -> Vec<Token<'a>> {
+        let mut tokens = Vec::new();
+        Tokenizer::with_keywords(input, &self.keywords).tokens_into(&mut tokens);
+        tokens
+    }
+
+    /// Parses a string into its element components.
+    ///
+    /// The returned elements borrow from the input string.
+    pub fn parse<'a>(&mut self, input: &'a str) -> Vec<Element<'a>> {
+        let mut results = Vec::new();
+        self.parse_into(input, &mut results);
+        results
+    }
+
+    /// Parses a string into its element components, storing them into the given buffer.
+    ///
+    /// The buffer is cleared beforehand, which allows reusing its allocation across calls.
+    pub fn parse_into<'a>(&mut self, input: &'a str, results: &mut Vec<Element<'a>>) {
+        let mut tokens = recycle_vec(std::mem::take(&mut self.tokens));
+        Tokenizer::with_keywords(input, &self.keywords).tokens_into(&mut tokens);
+        results.clear();
+        parse_tokens(&mut tokens, self.options, results, None);
+        self.tokens = recycle_vec(tokens);
+    }
+
+    /// Parses a string into its element components along with how each of them was found.
+    ///
+    /// This is slower than [`Parser::parse`] and is meant for debugging bad parses.
+    pub fn explain<'a>(&mut self, input: &'a str) -> Explanation<'a> {
+        let mut tokens = self.tokenize(input);
+        let mut results = Vec::new();
+        let mut trace = Trace::default();
+        parse_tokens(&mut tokens, self.options, &mut results, Some(&mut trace));
+        trace.into_explanation(&tokens, results)
+    }
+
+    /// Parses a string into up to `limit` candidate interpretations, ranked from best to worst.
+    ///
+    /// See [`crate::parse_candidates_with_options`] for more information.
+    pub fn candidates<'a>(&self, input: &'a str, limit: usize) -> Vec<Candidate<'a>> {
+        let tokens = self.tokenize(input);
+        crate::candidates::parse_candidates(&tokens, self.options, limit)
+    }
+
+    /// Parses a path along with the context of its directories.
+    ///
+    /// See [`crate::parse_path_with_options`] for how the components are merged.
+    pub fn parse_path<'a>(&mut self, path: &'a str) -> ParsedPath<'a> {
+        crate::path::parse_path(self, path)
+    }
+
+    /// Parses the names of a batch using the consensus between them.
+    ///
+    /// See [`crate::parse_batch_with_options`] for how the names are corrected.
+    pub fn parse_batch<'a>(&self, inputs: &[&'a str]) -> Vec<BatchEntry<'a>> {
+        crate::batch::parse_batch(self, inputs)
+    }
+}
+
+const EPISODE_RULES: &[Rule] = &[
+    Rule::EpisodeKeyword,
+    Rule::EpisodePrefix,
+    Rule::NumberInNumber,
+    Rule::EpisodeVersion,
+    Rule::EpisodeRange,
+    Rule::SeasonAndEpisode,
+    Rule::EpisodeAfterType,
+    Rule::NumberSign,
+    Rule::EpisodeJapanese,
+    Rule::EpisodeEquivalent,
+    Rule::SeparatedNumber,
+    Rule::FractionalEpisode,
+    Rule::EnclosedNumber,
+    Rule::PartialEpisode,
+    Rule::LastNumber,
+    Rule::NumberOfTotal,
+];
+
+/// The state shared by the stages of the parser.
+struct Pipeline<'p, 'a> {
+    tokens: &'p mut [Token<'a>],
+    results: &'p mut Vec<Element<'a>>,
+    trace: Option<&'p mut Trace>,
+}
+
+impl<'a> Pipeline<'_, 'a> {
+    /// Runs a stage of the parser, recording it into the trace if there is one.
+    fn stage<F>(&mut self, rules: &[Rule], report_unmatched: bool, f: F)
+    where
+        F: FnOnce(&mut [Token<'a>], &mut Vec<Element<'a>>),
+    {
+        match self.trace.as_deref_mut() {
+            Some(trace) => trace.stage(self.tokens, self.results, rules, report_unmatched, f),
+            None => f(self.tokens, self.results),
         }
     }
 
+    fn has_element(&self, kind: ElementKind) -> bool {
+        self.results.iter().any(|e| e.kind == kind)
+    }
+}
+
+pub(crate) fn parse_tokens<'a>(
+    tokens: &mut [Token<'a>],
+    options: Options,
+    results: &mut Vec<Element<'a>>,
+    trace: Option<&mut Trace>,
+) {
+    let mut pipeline = Pipeline {
+        tokens,
+        results,
+        trace,
+    };
+
+    if options.parse_file_extension() {
+        pipeline.stage(&[Rule::FileExtension], true, |tokens, results| {
+            results.extend(file_extension::parse_file_extension(tokens));
+        });
+    }
+
+    // Scene names follow stricter conventions, so they are detected even without the option
+    let is_scene = options.parse_scene() || scene::is_scene_name(pipeline.tokens);
+    if is_scene {
+        pipeline.stage(&[], false, |tokens, _| scene::prepare_tokens(tokens));
+    }
+
+    if options.parse_torrent() {
+        pipeline.stage(&[], false, |tokens, _| torrent::mark_file_size(tokens));
+    }
+
+    pipeline.stage(&[], false, |tokens, results| {
+        keywords::parse_keywords(tokens, &options, results);
+    });
+
+    if is_scene {
+        let rules = &[
+            Rule::SceneReleaseGroup,
+            Rule::SceneMultiEpisode,
+            Rule::SceneYear,
+        ];
+        pipeline.stage(rules, true, |tokens, results| {
+            scene::parse_scene(tokens, &options, results);
+        });
+    }
+
+    if options.parse_file_checksum() {
+        pipeline.stage(&[Rule::FileChecksum], true, |tokens, results| {
+            results.extend(file_checksum::parse_file_checksum(tokens));
+        });
+    }
+
     if options.parse_video_resolution() {
-        video_resolution::parse_video_resolution(&mut tokens, &mut results);
+        let rules = &[Rule::VideoResolution, Rule::VideoResolutionNumber];
+        pipeline.stage(rules, true, video_resolution::parse_video_resolution);
     }
 
     if options.parse_date() {
-        if let Some(el) = year::parse_date(&mut tokens) {
-            results.push(el);
-        }
+        pipeline.stage(&[Rule::Date], true, |tokens, results| {
+            results.extend(year::parse_date(tokens));
+        });
     }
 
     if options.parse_year() {
-        if let Some(el) = year::parse_year(&mut tokens) {
-            results.push(el);
-        }
+        let rules = &[Rule::EnclosedYear, Rule::IsolatedYear];
+        pipeline.stage(rules, true, |tokens, results| {
+            results.extend(year::parse_year(tokens));
+        });
     }
 
     if options.parse_season() {
-        season::parse_season(&mut tokens, &mut results);
+        let rules = &[
+            Rule::SeasonOrdinal,
+            Rule::SeasonKeyword,
+            Rule::SeasonRange,
+            Rule::SeasonPrefix,
+            Rule::SeasonJapanese,
+        ];
+        pipeline.stage(rules, true, season::parse_season);
     }
 
-    part::parse_part(&mut tokens, &mut results);
+    pipeline.stage(&[Rule::Part], true, part::parse_part);
 
     if options.parse_episode() {
-        volume::parse_volume(&mut tokens, &mut results);
-        episode::parse_episode(&mut tokens, &mut results, ElementKind::Episode);
+        pipeline.stage(&[Rule::TotalJapanese], true, episode::parse_japanese_total);
+        pipeline.stage(&[Rule::Volume], true, volume::parse_volume);
+        if options.parse_torrent() {
+            pipeline.stage(
+                &[Rule::SeparatedEpisodeRange],
+                true,
+                torrent::parse_episode_range,
+            );
+        }
+        pipeline.stage(EPISODE_RULES, true, |tokens, results| {
+            episode::parse_episode(tokens, results, ElementKind::Episode);
+        });
     }
 
     if options.parse_title() {
-        if let Some(title) = title::parse_title(&mut tokens) {
-            results.push(title);
-        }
+        let rules = &[Rule::Title, Rule::AlternativeTitle];
+        pipeline.stage(rules, true, |tokens, results| {
+            results.extend(title::parse_titles(tokens, &options));
+        });
     }
 
-    if options.parse_release_group() && !results.iter().any(|e| e.kind == ElementKind::ReleaseGroup)
-    {
-        if let Some(group) = release_group::parse_release_group(&mut tokens) {
-            results.push(group);
-        }
+    if options.parse_release_group() && !pipeline.has_element(ElementKind::ReleaseGroup) {
+        let rules = &[Rule::EnclosedReleaseGroup, Rule::ReleaseGroupSuffix];
+        pipeline.stage(rules, true, |tokens, results| {
+            results.extend(release_group::parse_release_group(tokens));
+        });
     }
 
-    let has_episode = results.iter().any(|e| e.kind == ElementKind::Episode);
-
-    if has_episode {
-        if options.parse_episode_title() {
-            if let Some(title) = episode_title::parse_episode_title(&mut tokens) {
-                results.push(title);
-            }
+    if pipeline.has_element(ElementKind::Episode) {
+        // A batch of episodes in a torrent title doesn't have an episode title
+        let is_batch = options.parse_torrent()
+            && pipeline
+                .results
+                .iter()
+                .filter(|e| e.kind == ElementKind::Episode)
+                .count()
+                > 1;
+        if options.parse_episode_title() && !is_batch {
+            pipeline.stage(&[Rule::EpisodeTitle], true, |tokens, results| {
+                results.extend(episode_title::parse_episode_title(tokens));
+            });
         }
 
         if options.parse_episode() {
-            episode::parse_episode(&mut tokens, &mut results, ElementKind::EpisodeAlt)
+            // The rules were already reported by the first pass, which takes precedence
+            pipeline.stage(&[], false, |tokens, results| {
+                episode::parse_episode(tokens, results, ElementKind::EpisodeAlt);
+            });
         }
     }
 
-    results.sort_by_key(|e| e.position);
-    results
+    if options.parse_unknown_tokens() {
+        pipeline.stage(&[], false, unknown::parse_unknown);
+    }
+
+    match pipeline.trace {
+        Some(trace) => trace.sort(pipeline.results),
+        None => pipeline.results.sort_by_key(|e| e.position),
+    }
 }
@@ -1,6 +1,7 @@
 use crate::{
     element::{Element, ElementKind},
     keyword::KeywordKind,
+    rule::Rule,
     tokenizer::Token,
     utils::*,
 };
@@ -21,7 +22,7 @@ pub fn parse_part<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>)
             continue;
         }
 
-        results.push(Element::new(ElementKind::Part, &tokens[next]));
+        results.push(Element::new(ElementKind::Part, Rule::Part, &tokens[next]));
         tokens[index].mark_known();
         tokens[next].mark_known();
     }
@@ -1,5 +1,6 @@
 use crate::{
     element::{Element, ElementKind},
+    rule::Rule,
     tokenizer::Token,
     utils::*,
 };
@@ -72,14 +73,15 @@ pub fn parse_release_group<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>>
     if value.is_empty() {
         None
     } else {
-        let position = range.first()?.position;
+        let rule = if range[0].is_enclosed {
+            Rule::EnclosedReleaseGroup
+        } else {
+            Rule::ReleaseGroupSuffix
+        };
+        let element = Element::from_tokens(ElementKind::ReleaseGroup, rule, range, value)?;
         for token in range {
             token.mark_known();
         }
-        Some(Element {
-            kind: ElementKind::ReleaseGroup,
-            value: value.into(),
-            position,
-        })
+        Some(element)
     }
 }
@@ -0,0 +1,254 @@
+use crate::{
+    element::{Element, ElementKind},
+    keyword::{Keyword, KeywordKind},
+    rule::Rule,
+    tokenizer::{Token, TokenKind},
+    utils::*,
+    Options,
+};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Release information that is only a keyword in scene names, since it is a common word
+/// otherwise, e.g. `Internal Affairs` or `Proper Noun`
+const SCENE_RELEASE_INFORMATION: &[&str] = &["INTERNAL", "PROPER"];
+
+/// A season with any number of episodes, e.g. `S01`, `S01E02` or `S01E01E02`
+fn season_marker_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"^(?i:S\d{1,2}(?:E\d{1,4})*)$"#).unwrap())
+}
+
+fn multi_episode_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"^(?i:S(\d{1,2})((?:E\d{1,4}){2,}))$"#).unwrap())
+}
+
+fn episode_range_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"^(?i:S(\d{1,2})E(\d{1,4}))$"#).unwrap())
+}
+
+/// Returns the index of the `-GROUP` suffix, skipping the file extension and a tracker tag
+fn find_group_suffix(tokens: &[Token<'_>]) -> Option<usize> {
+    let mut end = tokens.len();
+    if end >= 2
+        && tokens[end - 1].keyword_kind() == Some(KeywordKind::FileExtension)
+        && tokens[end - 2].value == "."
+    {
+        end -= 2;
+    }
+    // A tracker tag such as `[rarbg]` is appended after the name
+    if end > 0 && tokens[end - 1].is_closed_bracket() {
+        end = tokens[..end].iter().rposition(|t| t.is_open_bracket())?;
+    }
+
+    let last = end.checked_sub(1)?;
+    let token = &tokens[last];
+    let is_group = token.is_not_delimiter()
+        && !token.is_bracket()
+        && !token.is_enclosed
+        && last >= 2
+        && tokens[last - 1].is_delimiter()
+        && tokens[last - 1].value == "-";
+    is_group.then_some(last)
+}
+
+/// Returns the index of the first token after the title, e.g. `S01E02` or `1080p`
+fn find_marker(tokens: &[Token<'_>]) -> Option<usize> {
+    let first = tokens.iter().position(|t| t.is_not_delimiter())?;
+    tokens
+        .iter()
+        .enumerate()
+        .skip(first + 1)
+        .find_map(|(index, t)| {
+            let is_marker = season_marker_regex().is_match(t.value)
+                || t.keyword.is_some_and(|k| {
+                    !k.is_ambiguous()
+                        && matches!(k.kind, KeywordKind::VideoResolution | KeywordKind::Source)
+                });
+            is_marker.then_some(index)
+        })
+}
+
+fn is_release_information(token: &Token<'_>) -> bool {
+    token.keyword_kind() == Some(KeywordKind::ReleaseInformation)
+        || (token.is_text()
+            && SCENE_RELEASE_INFORMATION
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(token.value)))
+}
+
+/// Returns the index of the first token after the title and year, which is the marker or
+/// the release information before it, e.g. `REPACK` in `Title.2019.REPACK.1080p`
+fn find_title_end(tokens: &[Token<'_>], marker: usize) -> usize {
+    let first = tokens
+        .iter()
+        .position(|t| t.is_not_delimiter())
+        .unwrap_or(0);
+    let mut end = marker;
+    while let Some(index) = find_prev_token(tokens, Some(end), |t| t.is_not_delimiter()) {
+        if index <= first || !is_release_information(&tokens[index]) {
+            break;
+        }
+        end = index;
+    }
+    end
+}
+
+/// Returns whether the tokens look like a scene release, e.g. `Title.S01E02.1080p.WEB-GROUP`.
+///
+/// The name has to be separated by dots without any brackets, except for a tracker tag at
+/// the end, and has to end with a `-GROUP` suffix after a season, resolution or source.
+pub fn is_scene_name(tokens: &[Token<'_>]) -> bool {
+    let Some(group) = find_group_suffix(tokens) else {
+        return false;
+    };
+    let name = &tokens[..group];
+    let is_dotted = name.iter().all(|t| {
+        !t.is_bracket()
+            && !t.is_enclosed
+            && (t.is_not_delimiter() || t.value == "." || t.value == "-")
+    }) && name.iter().filter(|t| t.value == ".").count() >= 2;
+    is_dotted && find_marker(name).is_some()
+}
+
+/// Resolves ambiguous keywords by their position in a scene name.
+///
+/// Everything before the first marker is the title (and maybe a year), so keywords there are
+/// treated as text, e.g. `Movie` in `Movie.Name.2019`, while everything after it is metadata,
+/// e.g. `WEB` in `S01E02.WEB.h264`. Release information such as `PROPER` is metadata even
+/// right before the marker.
+pub fn prepare_tokens(tokens: &mut [Token<'_>]) {
+    let Some(marker) = find_marker(tokens) else {
+        return;
+    };
+    let title_end = find_title_end(tokens, marker);
+    for (index, token) in tokens.iter_mut().enumerate() {
+        if index >= title_end && token.is_text() && is_release_information(token) {
+            token.kind = TokenKind::Keyword;
+            token.keyword = Some(Keyword::new(KeywordKind::ReleaseInformation));
+            continue;
+        }
+        let Some(keyword) = token.keyword.filter(|k| k.is_ambiguous()) else {
+            continue;
+        };
+        if index < title_end {
+            token.kind = TokenKind::Text;
+            token.keyword = None;
+        } else {
+            token.keyword = Some(keyword.with_ambiguous(false));
+        }
+    }
+}
+
+fn parse_multi_episode<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>) {
+    let Some(marker) = find_marker(tokens).filter(|&i| tokens[i].is_free()) else {
+        return;
+    };
+
+    let value = tokens[marker].value;
+    if let Some(captures) = multi_episode_regex().captures(value) {
+        let token = &tokens[marker];
+        results.push(Element::with_value(
+            ElementKind::Season,
+            Rule::SceneMultiEpisode,
+            token,
+            captures.get(1).unwrap().as_str(),
+        ));
+        let episodes = captures.get(2).unwrap();
+        let mut offset = episodes.start();
+        for episode in episodes.as_str().split(['E', 'e']).skip(1) {
+            offset += 1;
+            results.push(Element::with_value(
+                ElementKind::Episode,
+                Rule::SceneMultiEpisode,
+                token,
+                &value[offset..offset + episode.len()],
+            ));
+            offset += episode.len();
+        }
+        tokens[marker].mark_known();
+        return;
+    }
+
+    // A range such as `S01E01-E03` is split into multiple tokens by the dash
+    let Some(captures) = episode_range_regex().captures(value) else {
+        return;
+    };
+    let Some(next) = find_next_token(tokens, marker, true, |t| t.is_not_delimiter()) else {
+        return;
+    };
+    let is_range = next == marker + 2
+        && tokens[marker + 1].value == "-"
+        && tokens[next].is_free()
+        && tokens[next].value.starts_with(['E', 'e'])
+        && super::common::is_valid_episode_number(&tokens[next].value[1..]);
+    if !is_range {
+        return;
+    }
+    results.push(Element::with_value(
+        ElementKind::Season,
+        Rule::SceneMultiEpisode,
+        &tokens[marker],
+        captures.get(1).unwrap().as_str(),
+    ));
+    results.push(Element::with_value(
+        ElementKind::Episode,
+        Rule::SceneMultiEpisode,
+        &tokens[marker],
+        captures.get(2).unwrap().as_str(),
+    ));
+    let last = tokens[next].value;
+    results.push(Element::with_value(
+        ElementKind::Episode,
+        Rule::SceneMultiEpisode,
+        &tokens[next],
+        &last[1..],
+    ));
+    for token in &mut tokens[marker..=next] {
+        token.mark_known();
+    }
+}
+
+/// Parses a year between the title and the first marker, e.g. `Title.2019.1080p`, skipping
+/// release information such as `REPACK` in `Title.2019.REPACK.1080p`
+fn parse_year<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
+    let marker = find_marker(tokens)?;
+    let title_end = find_title_end(tokens, marker);
+    let index = find_prev_token(tokens, Some(title_end), |t| t.is_not_delimiter())?;
+    let has_title = tokens[..index]
+        .iter()
+        .any(|t| t.is_free() && t.is_not_delimiter());
+    let token = &mut tokens[index];
+    if !(has_title && token.is_free() && token.is_number() && super::year::is_year(token.value)) {
+        return None;
+    }
+    token.mark_known();
+    Some(Element::new(ElementKind::Year, Rule::SceneYear, token))
+}
+
+pub fn parse_scene<'a>(
+    tokens: &mut [Token<'a>],
+    options: &Options,
+    results: &mut Vec<Element<'a>>,
+) {
+    if options.parse_release_group() {
+        if let Some(index) = find_group_suffix(tokens).filter(|&i| tokens[i].is_free()) {
+            tokens[index].mark_known();
+            results.push(Element::new(
+                ElementKind::ReleaseGroup,
+                Rule::SceneReleaseGroup,
+                &tokens[index],
+            ));
+        }
+    }
+
+    if options.parse_episode() {
+        parse_multi_episode(tokens, results);
+    }
+
+    if options.parse_year() {
+        results.extend(parse_year(tokens));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     element::{Element, ElementKind},
     keyword::KeywordKind,
+    rule::Rule,
     tokenizer::Token,
     utils::*,
 };
@@ -16,11 +17,12 @@ fn inner_parse_season<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
                 last.mark_known();
                 mid.mark_known();
                 first.mark_known();
-                return Some(Element {
-                    kind: ElementKind::Season,
-                    value: number.into(),
-                    position: first.position,
-                });
+                return Some(Element::with_value(
+                    ElementKind::Season,
+                    Rule::SeasonOrdinal,
+                    first,
+                    number,
+                ));
             }
         }
         if is_season_keyword(first) && mid.is_delimiter() && last.is_free() {
@@ -35,11 +37,12 @@ fn inner_parse_season<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
             last.mark_known();
             mid.mark_known();
             first.mark_known();
-            return Some(Element {
-                kind: ElementKind::Season,
-                value: value.into(),
-                position: last.position,
-            });
+            return Some(Element::with_value(
+                ElementKind::Season,
+                Rule::SeasonKeyword,
+                last,
+                value,
+            ));
         }
     }
     None
@@ -64,16 +67,18 @@ pub fn parse_season<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>
                         && s.bytes().all(|x: u8| x.is_ascii_digit())
                     {
                         token.mark_known();
-                        results.push(Element {
-                            kind: ElementKind::Season,
-                            value: f.into(),
-                            position: token.position,
-                        });
-                        results.push(Element {
-                            kind: ElementKind::Season,
-                            value: s.into(),
-                            position: token.position,
-                        });
+                        results.push(Element::with_value(
+                            ElementKind::Season,
+                            Rule::SeasonRange,
+                            token,
+                            f,
+                        ));
+                        results.push(Element::with_value(
+                            ElementKind::Season,
+                            Rule::SeasonRange,
+                            token,
+                            s,
+                        ));
                         continue;
                     }
                 }
@@ -81,11 +86,12 @@ pub fn parse_season<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>
 
             if (1..=2).contains(&suffix.len()) && suffix.bytes().all(|x| x.is_ascii_digit()) {
                 token.mark_known();
-                results.push(Element {
-                    kind: ElementKind::Season,
-                    value: suffix.into(),
-                    position: token.position,
-                });
+                results.push(Element::with_value(
+                    ElementKind::Season,
+                    Rule::SeasonPrefix,
+                    token,
+                    suffix,
+                ));
                 continue;
             }
         }
@@ -95,11 +101,12 @@ pub fn parse_season<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>
                 let prefix = prefix.strip_prefix('第').unwrap_or(prefix);
                 if (1..=2).contains(&prefix.len()) && prefix.bytes().all(|x| x.is_ascii_digit()) {
                     token.mark_known();
-                    results.push(Element {
-                        kind: ElementKind::Season,
-                        value: prefix.into(),
-                        position: token.position,
-                    });
+                    results.push(Element::with_value(
+                        ElementKind::Season,
+                        Rule::SeasonJapanese,
+                        token,
+                        prefix,
+                    ));
                 }
             }
         }
@@ -1,10 +1,17 @@
+use std::ops::Range;
+
 use crate::{
     element::{Element, ElementKind},
-    tokenizer::{combine_tokens, opposite_bracket, Token},
+    rule::Rule,
+    tokenizer::{combine_tokens, opposite_bracket, Token, TokenKind},
     utils::*,
+    Options,
 };
 
-pub fn find_title<'a, 'b>(tokens: &'b mut [Token<'a>]) -> Option<&'b mut [Token<'a>]> {
+/// The separators between a title and its alternative titles, e.g. `Title / Alt Title`
+pub const TITLE_SEPARATORS: &[&str] = &["/", "|"];
+
+fn find_title_range(tokens: &mut [Token<'_>]) -> Option<Range<usize>> {
     let mut first = tokens.iter().position(|t| t.is_free() && !t.is_enclosed);
     let mut last =
         first.and_then(|index| find_next_token(tokens, index, true, |t| t.is_identified()));
@@ -53,27 +60,170 @@ pub fn find_title<'a, 'b>(tokens: &'b mut [Token<'a>]) -> Option<&'b mut [Token<
         }
     }
 
-    match (first, last) {
-        (Some(x), Some(y)) => Some(&mut tokens[x..y]),
-        (Some(x), None) => Some(&mut tokens[x..]),
-        _ => None,
+    Some(index..last.unwrap_or(tokens.len()))
+}
+
+/// Returns whether the tokens have a letter outside of the Latin script, e.g. kana or kanji
+fn has_non_latin_letters(tokens: &[Token<'_>]) -> bool {
+    tokens
+        .iter()
+        .flat_map(|t| t.value.chars())
+        .any(|c| c.is_alphabetic() && c > '\u{024F}')
+}
+
+/// Returns whether the tokens in brackets are a translation of the title before them.
+///
+/// Brackets after a title are often part of it, e.g. `Girls und Panzer (Das Finale)`, so
+/// this needs a stronger signal: either the scripts differ, as in `進撃の巨人 (Attack on Titan)`,
+/// or most words are shared, as in `Yes! Precure 5 GoGo! (Yes! Pretty Cure 5 GoGo!)`. Torrent
+/// titles list translations far more often, so there two words are enough.
+fn is_translation(title: &[Token<'_>], tokens: &[Token<'_>], options: &Options) -> bool {
+    let is_text = tokens.iter().all(|t| {
+        t.is_delimiter() || (t.is_free() && matches!(t.kind, TokenKind::Text | TokenKind::Number))
+    });
+    let words: Vec<_> = tokens.iter().filter(|t| t.is_not_delimiter()).collect();
+    if !is_text || words.len() < 2 {
+        return false;
+    }
+    if options.parse_torrent() || has_non_latin_letters(title) != has_non_latin_letters(tokens) {
+        return true;
+    }
+    let shared = words
+        .iter()
+        .filter(|word| {
+            title
+                .iter()
+                .any(|t| t.is_not_delimiter() && t.value.eq_ignore_ascii_case(word.value))
+        })
+        .count();
+    shared * 2 >= words.len()
+}
+
+/// Returns the indices of the brackets around a translated title that follows the title,
+/// e.g. `(Attack on Titan)` in `進撃の巨人 (Attack on Titan) - 01` or `[Demon Slayer]`
+/// in `鬼滅の刃 [Demon Slayer] - 01`.
+///
+/// Single words such as `(Black)` in `Nekomonogatari (Black)` are more likely to be part of
+/// the title, so the translation needs at least two words.
+fn find_translation(
+    tokens: &[Token<'_>],
+    title: &Range<usize>,
+    options: &Options,
+) -> Option<(usize, usize)> {
+    // Parentheses are kept in the title, e.g. `Title (Translated Title)`
+    let last = find_prev_token(tokens, Some(title.end), |t| t.is_not_delimiter())?;
+    if last > title.start && tokens[last].value == ")" {
+        let open = find_prev_token(tokens, Some(last), |t| t.is_open_bracket())?;
+        let has_title = tokens[title.start..open]
+            .iter()
+            .any(|t| t.is_not_delimiter() && !t.is_enclosed);
+        let is_separate = open > title.start && tokens[open - 1].is_delimiter();
+        return (open >= title.start
+            && has_title
+            && is_separate
+            && is_translation(&tokens[title.start..open], &tokens[open + 1..last], options))
+        .then_some((open, last));
+    }
+
+    // Other brackets end the title, e.g. `Title [Translated Title] - 01`
+    let open = find_next_token(tokens, title.end, false, |t| t.is_not_delimiter())?;
+    if !(tokens[open].is_open_bracket() && tokens[open].value == "[") {
+        return None;
     }
+    let close = find_next_token(tokens, open, true, |t| t.is_bracket())?;
+    let next = find_next_token(tokens, close, true, |t| t.value != " " && t.value != "_")?;
+    let is_followed_by_dash = tokens[next].is_delimiter() && tokens[next].value == "-";
+    let is_translation = is_translation(&tokens[title.clone()], &tokens[open + 1..close], options);
+    (tokens[close].value == "]" && is_followed_by_dash && is_translation).then_some((open, close))
 }
 
-pub fn parse_title<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
-    let range = find_title(tokens)?;
-    let value = combine_tokens(range, crate::tokenizer::KeepDelimiters::No);
-    if value.is_empty() {
-        None
+/// Parses the title along with its alternative titles, which follow a separator, e.g. the
+/// English title in `Shingeki no Kyojin / Attack on Titan`, or are a translation in brackets.
+pub fn parse_titles<'a>(tokens: &mut [Token<'a>], options: &Options) -> Vec<Element<'a>> {
+    let Some(mut range) = find_title_range(tokens) else {
+        return Vec::new();
+    };
+    let separators = if options.parse_torrent() {
+        super::torrent::TITLE_SEPARATORS
     } else {
-        let position = range.first()?.position;
-        for token in range {
+        TITLE_SEPARATORS
+    };
+    let translation = find_translation(tokens, &range, options);
+    if let Some((open, _)) = translation {
+        range.end = range.end.min(open);
+    }
+
+    let mut elements = Vec::new();
+    let is_space = |t: &Token<'_>| t.is_delimiter() && matches!(t.value, " " | "_");
+    let mut rest = &tokens[range.clone()];
+    let mut after_separator = false;
+    loop {
+        let end = rest
+            .iter()
+            .position(|t| separators.contains(&t.value))
+            .unwrap_or(rest.len());
+        // Only the spaces around a separator are trimmed, the rest is up to `combine_tokens`
+        let mut segment = &rest[..end];
+        if after_separator {
+            let first = segment.iter().position(|t| !is_space(t)).unwrap_or(end);
+            segment = &segment[first..];
+        }
+        if end < rest.len() {
+            let last = segment
+                .iter()
+                .rposition(|t| !is_space(t))
+                .map_or(0, |i| i + 1);
+            segment = &segment[..last];
+        }
+        if segment.iter().any(|t| t.is_not_delimiter()) {
+            let (kind, rule) = if elements.is_empty() {
+                (ElementKind::Title, Rule::Title)
+            } else {
+                (ElementKind::AlternativeTitle, Rule::AlternativeTitle)
+            };
+            let value = combine_tokens(segment, crate::tokenizer::KeepDelimiters::No);
+            if !value.is_empty() {
+                elements.extend(Element::from_tokens(kind, rule, segment, value));
+            }
+        }
+        match rest.get(end + 1..) {
+            Some(next) => {
+                rest = next;
+                after_separator = true;
+            }
+            None => break,
+        }
+    }
+    // A title that is only a separator, e.g. `/`, is kept as it is
+    if elements.is_empty() {
+        let title = &tokens[range.clone()];
+        let value = combine_tokens(title, crate::tokenizer::KeepDelimiters::No);
+        if value.is_empty() {
+            return elements;
+        }
+        elements.extend(Element::from_tokens(
+            ElementKind::Title,
+            Rule::Title,
+            title,
+            value,
+        ));
+    }
+    for token in &mut tokens[range] {
+        token.mark_known();
+    }
+
+    if let Some((open, close)) = translation {
+        let inner = &tokens[open + 1..close];
+        let value = combine_tokens(inner, crate::tokenizer::KeepDelimiters::No);
+        elements.extend(Element::from_tokens(
+            ElementKind::AlternativeTitle,
+            Rule::AlternativeTitle,
+            inner,
+            value,
+        ));
+        for token in &mut tokens[open..=close] {
             token.mark_known();
         }
-        Some(Element {
-            kind: ElementKind::Title,
-            value: value.into(),
-            position,
-        })
     }
+    elements
 }
@@ -0,0 +1,118 @@
+use crate::{
+    element::{Element, ElementKind},
+    rule::Rule,
+    tokenizer::Token,
+    utils::*,
+};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// The separators between the titles of a torrent title, which also uses `+` unlike file
+/// names, e.g. `Title + Alt Title`
+pub const TITLE_SEPARATORS: &[&str] = &["/", "|", "+"];
+
+/// A size with its unit in a single token, e.g. `1.4GiB` or `5GB` in `12.5GB`
+fn size_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"^(?i:\d+(?:[.,]\d+)?[KMGT]i?B)$"#).unwrap())
+}
+
+fn size_unit_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"^(?i:[KMGT]i?B)$"#).unwrap())
+}
+
+fn is_size_number(token: &Token<'_>) -> bool {
+    token.is_free()
+        && !token.value.is_empty()
+        && token
+            .value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b'.' || b == b',')
+}
+
+/// Marks the size of a torrent, e.g. `1.4 GiB` or `[12.5GB]`, so that it isn't mistaken for
+/// an episode, an episode title or a release group
+pub fn mark_file_size(tokens: &mut [Token<'_>]) {
+    for index in 0..tokens.len() {
+        if !tokens[index].is_free() {
+            continue;
+        }
+        if size_regex().is_match(tokens[index].value) {
+            tokens[index].mark_known();
+            continue;
+        }
+        if !is_size_number(&tokens[index]) {
+            continue;
+        }
+        let Some(next) = find_next_token(tokens, index, true, |t| t.is_not_delimiter()) else {
+            continue;
+        };
+        let separator = &tokens[index + 1..next];
+        let is_size = match separator {
+            // `1.4 GiB`
+            [space] if space.value == " " => size_unit_regex().is_match(tokens[next].value),
+            // `12.5GB` is split into `12`, `.` and `5GB` since `5GB` isn't a number
+            [dot] if dot.value == "." => {
+                size_regex().is_match(tokens[next].value)
+                    && tokens[next].value.starts_with(|c: char| c.is_ascii_digit())
+            }
+            _ => false,
+        };
+        if is_size && tokens[next].is_free() {
+            for token in &mut tokens[index..=next] {
+                token.mark_known();
+            }
+        }
+    }
+}
+
+/// Parses a range of episodes that is separated by spaces, e.g. `01 ~ 12` or `(01 - 12)`
+pub fn parse_episode_range<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>) {
+    for index in 0..tokens.len() {
+        if !(tokens[index].is_free() && tokens[index].is_number()) {
+            continue;
+        }
+        // A tilde isn't a delimiter, so it is skipped along with the spaces around it
+        let Some(next) = find_next_token(tokens, index, true, |t| {
+            t.is_not_delimiter() && t.value != "~"
+        }) else {
+            continue;
+        };
+        if !(tokens[next].is_free() && tokens[next].is_number()) {
+            continue;
+        }
+        let separators: Vec<&str> = tokens[index + 1..next]
+            .iter()
+            .map(|t| t.value)
+            .filter(|v| *v != " ")
+            .collect();
+        let is_range = match separators.as_slice() {
+            ["~"] => true,
+            ["-"] => tokens[index].is_enclosed && tokens[next].is_enclosed,
+            _ => false,
+        };
+        let bounds = tokens[index]
+            .value
+            .parse::<u16>()
+            .ok()
+            .zip(tokens[next].value.parse::<u16>().ok());
+        if !(is_range && bounds.is_some_and(|(lower, upper)| lower < upper)) {
+            continue;
+        }
+        results.push(Element::new(
+            ElementKind::Episode,
+            Rule::SeparatedEpisodeRange,
+            &tokens[index],
+        ));
+        results.push(Element::new(
+            ElementKind::Episode,
+            Rule::SeparatedEpisodeRange,
+            &tokens[next],
+        ));
+        for token in &mut tokens[index..=next] {
+            token.mark_known();
+        }
+        return;
+    }
+}
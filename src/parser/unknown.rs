@@ -0,0 +1,20 @@
+use crate::{
+    element::{Element, ElementKind},
+    rule::Rule,
+    tokenizer::Token,
+};
+
+pub fn parse_unknown<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>) {
+    let claimed = results.len();
+    for token in tokens.iter_mut().filter(|t| t.is_free()) {
+        // Some elements do not mark their tokens as known (e.g. ambiguous keywords)
+        let is_claimed = results[..claimed]
+            .iter()
+            .any(|e| e.span.start < token.span.end && token.span.start < e.span.end);
+        if is_claimed {
+            continue;
+        }
+        token.mark_known();
+        results.push(Element::new(ElementKind::Unknown, Rule::Unknown, token));
+    }
+}
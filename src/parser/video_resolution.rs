@@ -1,5 +1,6 @@
 use crate::{
     element::{Element, ElementKind},
+    rule::Rule,
     tokenizer::Token,
 };
 use regex::Regex;
@@ -21,7 +22,11 @@ pub fn parse_video_resolution<'a>(tokens: &mut [Token<'a>], results: &mut Vec<El
         .filter(|t| t.is_free() && is_video_resolution(t.value))
     {
         token.mark_known();
-        results.push(Element::new(ElementKind::VideoResolution, token));
+        results.push(Element::new(
+            ElementKind::VideoResolution,
+            Rule::VideoResolution,
+            token,
+        ));
         found = true;
     }
 
@@ -30,7 +35,11 @@ pub fn parse_video_resolution<'a>(tokens: &mut [Token<'a>], results: &mut Vec<El
             .iter_mut()
             .find(|t| t.is_free() && t.is_number() && (t.value == "1080" || t.value == "720"))
         {
-            results.push(Element::new(ElementKind::VideoResolution, token));
+            results.push(Element::new(
+                ElementKind::VideoResolution,
+                Rule::VideoResolutionNumber,
+                token,
+            ));
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::{
     element::{Element, ElementKind},
     keyword::KeywordKind,
+    rule::Rule,
     tokenizer::Token,
     utils::*,
 };
@@ -21,7 +22,33 @@ pub fn parse_volume<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>
             continue;
         }
 
-        if super::episode::parse_multi_episode_range(tokens, next, results, ElementKind::Volume) {
+        if super::episode::parse_multi_episode_range(
+            tokens,
+            next,
+            results,
+            ElementKind::Volume,
+            Rule::Volume,
+        ) {
+            tokens[index].mark_known();
+            tokens[next].mark_known();
+            continue;
+        }
+
+        // A volume along with the total number of volumes, e.g. `Vol.03/05`
+        if let Some((number, total)) = super::episode::parse_number_of_total(tokens[next].value) {
+            let token = &tokens[next];
+            results.push(Element::with_value(
+                ElementKind::Volume,
+                Rule::Volume,
+                token,
+                number,
+            ));
+            results.push(Element::with_value(
+                ElementKind::VolumeTotal,
+                Rule::Volume,
+                token,
+                total,
+            ));
             tokens[index].mark_known();
             tokens[next].mark_known();
             continue;
@@ -31,19 +58,33 @@ pub fn parse_volume<'a>(tokens: &mut [Token<'a>], results: &mut Vec<Element<'a>>
         else {
             continue;
         };
-        results.push(Element {
-            kind: ElementKind::Volume,
-            value: prefix.into(),
-            position: index,
-        });
+        results.push(Element::with_value(
+            ElementKind::Volume,
+            Rule::Volume,
+            &tokens[next],
+            prefix,
+        ));
         if !suffix.is_empty() {
-            results.push(Element {
-                kind: ElementKind::ReleaseVersion,
-                value: suffix.into(),
-                position: index,
-            })
+            results.push(Element::with_value(
+                ElementKind::ReleaseVersion,
+                Rule::Volume,
+                &tokens[next],
+                suffix,
+            ))
         }
         tokens[index].mark_known();
         tokens[next].mark_known();
+
+        // The total number of volumes, e.g. `Vol. 3 of 5`
+        if let Some(total) = super::episode::find_total_of(tokens, next) {
+            results.push(Element::new(
+                ElementKind::VolumeTotal,
+                Rule::Volume,
+                &tokens[total],
+            ));
+            for token in &mut tokens[next + 1..=total] {
+                token.mark_known();
+            }
+        }
     }
 }
@@ -1,10 +1,11 @@
 use crate::{
     element::{Element, ElementKind},
+    rule::Rule,
     tokenizer::Token,
     utils::*,
 };
 
-fn is_year(s: &str) -> bool {
+pub fn is_year(s: &str) -> bool {
     s.parse::<u16>()
         .ok()
         .is_some_and(|x| (1950..=2050).contains(&x))
@@ -35,7 +36,7 @@ pub fn parse_year<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
         .and_then(|idx| tokens.get_mut(idx))
     {
         token.mark_known();
-        return Some(Element::new(ElementKind::Year, token));
+        return Some(Element::new(ElementKind::Year, Rule::EnclosedYear, token));
     }
 
     for index in tokens
@@ -45,7 +46,11 @@ pub fn parse_year<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
     {
         if super::common::is_token_isolated(tokens, index) {
             tokens[index].mark_known();
-            return Some(Element::new(ElementKind::Year, &tokens[index]));
+            return Some(Element::new(
+                ElementKind::Year,
+                Rule::IsolatedYear,
+                &tokens[index],
+            ));
         }
     }
 
@@ -77,6 +82,8 @@ pub fn parse_date<'a>(tokens: &mut [Token<'a>]) -> Option<Element<'a>> {
                 year_month.value, delimiter.value, day.value
             )),
             position: year_month.position,
+            span: year_month.span.start..day.span.end,
+            rule: Rule::Date,
         });
     }
 
@@ -0,0 +1,249 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::{
+    element::{Element, ElementKind},
+    Parser,
+};
+
+/// An element of a path along with the component it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathElement<'a> {
+    /// The element, whose span is relative to the whole path
+    pub element: Element<'a>,
+    /// The index of the component in [`ParsedPath::components`] that the element came from
+    pub component: usize,
+}
+
+/// The result of parsing a path, see [`crate::parse_path_with_options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedPath<'a> {
+    /// The byte ranges of the components of the path, the last one is the file name
+    pub components: Vec<Range<usize>>,
+    /// The merged elements, sorted by their position in the path
+    pub elements: Vec<PathElement<'a>>,
+}
+
+impl<'a> ParsedPath<'a> {
+    /// Returns an iterator over the merged elements without their provenance
+    pub fn elements(&self) -> impl Iterator<Item = &Element<'a>> {
+        self.elements.iter().map(|e| &e.element)
+    }
+
+    /// Returns the index of the file name in [`ParsedPath::components`]
+    pub fn file_name_component(&self) -> Option<usize> {
+        self.components.len().checked_sub(1)
+    }
+}
+
+/// Kinds that only ever describe the file itself, so they are never taken from a directory
+const FILE_ONLY_KINDS: &[ElementKind] = &[
+    ElementKind::Episode,
+    ElementKind::EpisodeAlt,
+    ElementKind::EpisodeTitle,
+    ElementKind::FileChecksum,
+    ElementKind::FileExtension,
+    ElementKind::ReleaseVersion,
+    ElementKind::Unknown,
+];
+
+/// Splits a path on both `/` and `\`, skipping empty and `.` components
+fn split_components(path: &str) -> Vec<Range<usize>> {
+    let mut components = Vec::new();
+    let mut start = 0;
+    for (index, c) in path.char_indices().chain([(path.len(), '/')]) {
+        if c == '/' || c == '\\' {
+            let component = &path[start..index];
+            if !component.is_empty() && component != "." {
+                components.push(start..index);
+            }
+            start = index + c.len_utf8();
+        }
+    }
+    components
+}
+
+/// Moves the span of an element by the given offset and makes it own its value
+fn offset_element(element: Element<'_>, offset: usize) -> Element<'static> {
+    Element {
+        kind: element.kind,
+        value: Cow::Owned(element.value.into_owned()),
+        position: element.position,
+        span: (element.span.start + offset)..(element.span.end + offset),
+        rule: element.rule,
+    }
+}
+
+/// Returns the title of a directory, unless it is only a type such as `Specials`
+fn directory_title<'e, 'a>(elements: &'e [Element<'a>]) -> Option<&'e Element<'a>> {
+    let title = elements.iter().find(|e| e.kind == ElementKind::Title)?;
+    let is_type = elements
+        .iter()
+        .any(|e| e.kind == ElementKind::Type && e.value.eq_ignore_ascii_case(&title.value));
+    (!is_type).then_some(title)
+}
+
+/// Parses the file name as if it came after `prefix`, e.g. `Title - 05.mkv` for `05.mkv`.
+///
+/// Returns the elements of the file name if an episode was found this way.
+fn parse_after<'a>(
+    parser: &mut Parser,
+    prefix: &str,
+    path: &'a str,
+    file_name: &Range<usize>,
+) -> Option<Vec<Element<'a>>> {
+    let prefix = format!("{prefix} - ");
+    let combined = format!("{prefix}{}", &path[file_name.clone()]);
+    let reparsed = parser.parse(&combined);
+    if !reparsed.iter().any(|e| e.kind == ElementKind::Episode) {
+        return None;
+    }
+    let offset = file_name.start;
+    let elements = reparsed
+        .into_iter()
+        .filter(|e| e.span.start >= prefix.len())
+        .map(|e| {
+            let element = offset_element(e, offset);
+            Element {
+                span: (element.span.start - prefix.len())..(element.span.end - prefix.len()),
+                ..element
+            }
+        })
+        .collect();
+    Some(elements)
+}
+
+pub(crate) fn parse_path<'a>(parser: &mut Parser, path: &'a str) -> ParsedPath<'a> {
+    let components = split_components(path);
+    let Some((file_name, directories)) = components.split_last() else {
+        return ParsedPath::default();
+    };
+    let file_index = directories.len();
+
+    // Directories don't have file extensions, e.g. `Show.Name.S02` is not a `S02` file
+    let options = parser.options();
+    *parser.options_mut() = options.file_extensions(false);
+    let parsed_directories: Vec<Vec<Element<'a>>> = directories
+        .iter()
+        .map(|range| {
+            parser
+                .parse(&path[range.clone()])
+                .into_iter()
+                .map(|e| Element {
+                    span: (e.span.start + range.start)..(e.span.end + range.start),
+                    ..e
+                })
+                .collect()
+        })
+        .collect();
+    *parser.options_mut() = options;
+
+    // The title only comes from the nearest directory that has one
+    let title_directory = (0..directories.len())
+        .rev()
+        .find_map(|index| directory_title(&parsed_directories[index]).map(|t| (index, t)));
+
+    let mut file_elements: Vec<Element<'a>> = parser
+        .parse(&path[file_name.clone()])
+        .into_iter()
+        .map(|e| Element {
+            span: (e.span.start + file_name.start)..(e.span.end + file_name.start),
+            ..e
+        })
+        .collect();
+    let has = |elements: &[Element<'_>], kind| elements.iter().any(|e| e.kind == kind);
+
+    let mut elements: Vec<PathElement<'a>> = Vec::new();
+    if let Some((_, title)) = title_directory {
+        // A file name such as `05 - Episode Title.mkv` only makes sense after the title, so
+        // it is parsed again as if the title of the directory came before it
+        if !has(&file_elements, ElementKind::Episode) {
+            if let Some(reparsed) = parse_after(parser, &title.value, path, file_name) {
+                file_elements = reparsed;
+            }
+        }
+
+        // A title that comes after the episode, as in `S02E05 - Episode Title.mkv`, is the
+        // title of the episode since the directory already gives the title of the series
+        let episode_start = file_elements
+            .iter()
+            .filter(|e| e.kind == ElementKind::Episode)
+            .map(|e| e.span.start)
+            .min();
+        if let Some(episode_start) = episode_start {
+            let is_late_title =
+                |e: &Element<'_>| e.kind == ElementKind::Title && e.span.start > episode_start;
+            if has(&file_elements, ElementKind::EpisodeTitle) {
+                file_elements.retain(|e| !is_late_title(e));
+            } else {
+                for element in file_elements.iter_mut().filter(|e| is_late_title(e)) {
+                    element.kind = ElementKind::EpisodeTitle;
+                }
+            }
+        }
+    }
+
+    // A file name such as `05.mkv` in a directory such as `Specials` is only an episode, even
+    // though there is no title that it could come after
+    let type_directory = directories
+        .len()
+        .checked_sub(1)
+        .map(|index| &parsed_directories[index])
+        .filter(|elements| directory_title(elements).is_none())
+        .and_then(|elements| elements.iter().find(|e| e.kind == ElementKind::Type));
+    if let (None, Some(kind)) = (title_directory, type_directory) {
+        if !has(&file_elements, ElementKind::Episode) {
+            if let Some(reparsed) = parse_after(parser, &kind.value, path, file_name) {
+                file_elements = reparsed;
+            }
+        }
+    }
+
+    elements.extend(file_elements.into_iter().map(|element| PathElement {
+        element,
+        component: file_index,
+    }));
+
+    // Every other kind that the file name lacks is taken from the nearest directory with it
+    for index in (0..directories.len()).rev() {
+        for element in &parsed_directories[index] {
+            let kind = element.kind;
+            if FILE_ONLY_KINDS.contains(&kind) {
+                continue;
+            }
+            if kind == ElementKind::Title && title_directory.map(|(i, _)| i) != Some(index) {
+                continue;
+            }
+            let taken = elements
+                .iter()
+                .any(|e| e.element.kind == kind && e.component != index);
+            if !taken {
+                elements.push(PathElement {
+                    element: element.clone(),
+                    component: index,
+                });
+            }
+        }
+    }
+
+    elements.sort_by_key(|e| e.element.span.start);
+    ParsedPath {
+        components,
+        elements,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_components() {
+        let path = "/media//Show/./Season 2\\05.mkv";
+        let components: Vec<_> = split_components(path)
+            .into_iter()
+            .map(|range| &path[range])
+            .collect();
+        assert_eq!(components, ["media", "Show", "Season 2", "05.mkv"]);
+        assert!(split_components("").is_empty());
+    }
+}
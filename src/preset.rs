@@ -0,0 +1,292 @@
+use std::{path::PathBuf, sync::OnceLock};
+
+use crate::{
+    element::{Element, ElementKind},
+    format::Template,
+};
+
+/// What a parsed name refers to, which decides where a media server expects the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaKind {
+    /// A regular episode of a series
+    Episode,
+    /// An episode that isn't part of a regular season, e.g. an OVA or an opening
+    Special,
+    /// A standalone release
+    Movie,
+}
+
+impl MediaKind {
+    /// Decides what the elements refer to based on their `Type`, `Season` and `Episode`.
+    ///
+    /// A `Type` such as `Movie` or `Gekijouban` makes it a movie and one such as `OVA`,
+    /// `Special` or `NCOP` makes it a special, as does season `0`. Otherwise it is an episode,
+    /// unless neither an episode nor a season was found in which case it is a movie.
+    pub fn classify(elements: &[Element<'_>]) -> Self {
+        let types = || {
+            elements
+                .iter()
+                .filter(|e| e.kind == ElementKind::Type)
+                .map(|e| e.value.to_ascii_lowercase())
+        };
+        if types().any(|t| matches!(t.as_str(), "movie" | "gekijouban")) {
+            return Self::Movie;
+        }
+
+        let is_special_type = |t: String| {
+            matches!(
+                t.as_str(),
+                "oad"
+                    | "oav"
+                    | "ona"
+                    | "ova"
+                    | "sp"
+                    | "special"
+                    | "specials"
+                    | "op"
+                    | "opening"
+                    | "ed"
+                    | "ending"
+                    | "nced"
+                    | "ncop"
+                    | "preview"
+                    | "pv"
+            )
+        };
+        let is_season_zero = elements
+            .iter()
+            .any(|e| e.kind == ElementKind::Season && e.value.trim_start_matches('0').is_empty());
+        if types().any(is_special_type) || is_season_zero {
+            return Self::Special;
+        }
+
+        let has = |kind| elements.iter().any(|e| e.kind == kind);
+        if has(ElementKind::Episode) || has(ElementKind::Season) {
+            Self::Episode
+        } else {
+            Self::Movie
+        }
+    }
+}
+
+/// A naming scheme of a media server.
+///
+/// Series are stored as `Title/Season 02/<episode>`, where specials go into `Season 00`, and
+/// movies as `Title (Year)/Title (Year).mkv`. The presets only differ in how episodes are named:
+///
+/// | Preset     | Episode                | Multiple episodes          |
+/// |------------|------------------------|----------------------------|
+/// | `Plex`     | `Title - s02e05.mkv`   | `Title - s02e05-e06.mkv`   |
+/// | `Jellyfin` | `Title S02E05.mkv`     | `Title S02E05-E06.mkv`     |
+/// | `Kodi`     | `Title S02E05.mkv`     | `Title S02E05E06.mkv`      |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    Plex,
+    Jellyfin,
+    Kodi,
+}
+
+/// The templates of every path component, the last one is the file name
+struct Templates {
+    episode: [Template; 3],
+    special: [Template; 3],
+    movie: [Template; 2],
+}
+
+const SERIES_TEMPLATE: &str = "{title}";
+const SEASON_TEMPLATE: &str = "Season {?season:{season:02}}{!season:01}";
+const SPECIAL_SEASON_TEMPLATE: &str = "Season 00";
+const MOVIE_TEMPLATE: &str = "{title}{?year: ({year})}";
+const EXTENSION_TEMPLATE: &str = "{?file_extension:.{file_extension}}";
+
+impl Preset {
+    /// Returns the name of the preset in lowercase, e.g. `plex`
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Preset::Plex => "plex",
+            Preset::Jellyfin => "jellyfin",
+            Preset::Kodi => "kodi",
+        }
+    }
+
+    /// Returns the preset with the given name, ignoring ASCII case
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Preset::Plex, Preset::Jellyfin, Preset::Kodi]
+            .into_iter()
+            .find(|p| p.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the file name templates for episodes and specials, without the extension
+    fn episode_templates(&self) -> (&'static str, &'static str) {
+        match self {
+            Preset::Plex => (
+                "{title} - s{?season:{season:02}}{!season:01}e{episode:02:join(-e)}",
+                "{title} - s00e{?episode:{episode:02:join(-e)}}{!episode:01}",
+            ),
+            Preset::Jellyfin => (
+                "{title} S{?season:{season:02}}{!season:01}E{episode:02:join(-E)}",
+                "{title} S00E{?episode:{episode:02:join(-E)}}{!episode:01}",
+            ),
+            Preset::Kodi => (
+                "{title} S{?season:{season:02}}{!season:01}E{episode:02:join(E)}",
+                "{title} S00E{?episode:{episode:02:join(E)}}{!episode:01}",
+            ),
+        }
+    }
+
+    fn templates(&self) -> &'static Templates {
+        static TEMPLATES: [OnceLock<Templates>; 3] =
+            [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+        TEMPLATES[*self as usize].get_or_init(|| {
+            let template = |s: &str| Template::new(s).expect("preset templates are valid");
+            let (episode, special) = self.episode_templates();
+            Templates {
+                episode: [
+                    template(SERIES_TEMPLATE),
+                    template(SEASON_TEMPLATE),
+                    template(&format!("{episode}{EXTENSION_TEMPLATE}")),
+                ],
+                special: [
+                    template(SERIES_TEMPLATE),
+                    template(SPECIAL_SEASON_TEMPLATE),
+                    template(&format!("{special}{EXTENSION_TEMPLATE}")),
+                ],
+                movie: [
+                    template(MOVIE_TEMPLATE),
+                    template(&format!("{MOVIE_TEMPLATE}{EXTENSION_TEMPLATE}")),
+                ],
+            }
+        })
+    }
+
+    /// Returns the relative path that the media server expects the parsed file at.
+    ///
+    /// Characters that aren't allowed in file names, such as `/` in `Fate/Zero`, are replaced
+    /// in every component of the path. Returns `None` if no title was found.
+    pub fn path(&self, elements: &[Element<'_>]) -> Option<PathBuf> {
+        if !elements.iter().any(|e| e.kind == ElementKind::Title) {
+            return None;
+        }
+        let templates = self.templates();
+        let components: &[Template] = match MediaKind::classify(elements) {
+            MediaKind::Episode => &templates.episode,
+            MediaKind::Special => &templates.special,
+            MediaKind::Movie => &templates.movie,
+        };
+        Some(
+            components
+                .iter()
+                .map(|t| sanitize_component(&t.render(elements)))
+                .collect(),
+        )
+    }
+}
+
+/// Replaces the characters that are reserved on common file systems with spaces
+pub(crate) fn sanitize_component(component: &str) -> String {
+    let replaced: String = component
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let mut sanitized = String::with_capacity(replaced.len());
+    for word in replaced.split(' ').filter(|w| !w.is_empty()) {
+        if !sanitized.is_empty() {
+            sanitized.push(' ');
+        }
+        sanitized.push_str(word);
+    }
+    // Windows doesn't allow trailing dots in file names
+    sanitized.trim_end_matches('.').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(preset: Preset, input: &str) -> Option<String> {
+        preset
+            .path(&crate::parse(input))
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+    }
+
+    #[test]
+    fn test_classify() {
+        let classify = |input: &str| MediaKind::classify(&crate::parse(input));
+        assert_eq!(
+            classify("[Group] Title S2 - 05 [1080p].mkv"),
+            MediaKind::Episode
+        );
+        assert_eq!(
+            classify("[Group] Title - 05 [1080p].mkv"),
+            MediaKind::Episode
+        );
+        assert_eq!(
+            classify("[Group] Title (OVA) - 02 [1080p].mkv"),
+            MediaKind::Special
+        );
+        assert_eq!(
+            classify("[Group] Title - NCOP [1080p].mkv"),
+            MediaKind::Special
+        );
+        assert_eq!(
+            classify("[Group] Title Movie (2019) [1080p].mkv"),
+            MediaKind::Movie
+        );
+        assert_eq!(
+            classify("[Group] Title (2019) [1080p].mkv"),
+            MediaKind::Movie
+        );
+    }
+
+    #[test]
+    fn test_preset_paths() {
+        let input = "[Group] Show Name S2 - 05 [1080p].mkv";
+        assert_eq!(
+            path(Preset::Plex, input).as_deref(),
+            Some("Show Name/Season 02/Show Name - s02e05.mkv")
+        );
+        assert_eq!(
+            path(Preset::Jellyfin, input).as_deref(),
+            Some("Show Name/Season 02/Show Name S02E05.mkv")
+        );
+
+        let input = "[Group] Show Name - 01-02 [1080p].mkv";
+        assert_eq!(
+            path(Preset::Plex, input).as_deref(),
+            Some("Show Name/Season 01/Show Name - s01e01-e02.mkv")
+        );
+        assert_eq!(
+            path(Preset::Jellyfin, input).as_deref(),
+            Some("Show Name/Season 01/Show Name S01E01-E02.mkv")
+        );
+        assert_eq!(
+            path(Preset::Kodi, input).as_deref(),
+            Some("Show Name/Season 01/Show Name S01E01E02.mkv")
+        );
+
+        assert_eq!(
+            path(Preset::Plex, "[Group] Show Name (OVA) - 2 [1080p].mkv").as_deref(),
+            Some("Show Name/Season 00/Show Name - s00e02.mkv")
+        );
+        assert_eq!(
+            path(Preset::Kodi, "[Group] Movie Title (2019) [1080p].mkv").as_deref(),
+            Some("Movie Title (2019)/Movie Title (2019).mkv")
+        );
+        assert_eq!(
+            path(Preset::Plex, "[Group] Fate/Zero - 01 [1080p].mkv").as_deref(),
+            Some("Fate Zero/Season 01/Fate Zero - s01e01.mkv")
+        );
+        assert_eq!(path(Preset::Plex, "[Group][1080p].mkv"), None);
+    }
+
+    #[test]
+    fn test_sanitize_component() {
+        assert_eq!(sanitize_component("Re:Zero"), "Re Zero");
+        assert_eq!(sanitize_component("What?  No..."), "What No");
+        assert_eq!(sanitize_component("Title - 01.mkv"), "Title - 01.mkv");
+    }
+}
@@ -0,0 +1,138 @@
+//! Python bindings, built as the `anitomy` extension module with [maturin](https://www.maturin.rs/).
+
+use pyo3::{
+    exceptions::PyTypeError,
+    prelude::*,
+    types::{PyDict, PyList, PyTuple},
+};
+
+use crate::{Element, Options};
+
+fn options_from_kwargs(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Options> {
+    let mut options = Options::default();
+    let Some(kwargs) = kwargs else {
+        return Ok(options);
+    };
+    for (key, value) in kwargs.iter() {
+        let key: String = key.extract()?;
+        let toggle: bool = value.extract()?;
+        options = match key.as_str() {
+            "episode" => options.episodes(toggle),
+            "episode_title" => options.episode_titles(toggle),
+            "file_checksum" => options.file_checksums(toggle),
+            "file_extension" => options.file_extensions(toggle),
+            "release_group" => options.release_groups(toggle),
+            "season" => options.seasons(toggle),
+            "title" => options.titles(toggle),
+            "video_resolution" => options.video_resolutions(toggle),
+            "year" => options.years(toggle),
+            "date" => options.dates(toggle),
+            "unknown_tokens" => options.unknown_tokens(toggle),
+            "scene" => options.scene(toggle),
+            "torrent" => options.torrent(toggle),
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "unexpected keyword argument '{key}'"
+                )))
+            }
+        };
+    }
+    Ok(options)
+}
+
+/// Builds a dict with one key per kind, where the latest element wins like [`crate::ElementObject`]
+fn elements_to_dict<'py>(
+    py: Python<'py>,
+    elements: &[Element<'_>],
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for element in elements {
+        dict.set_item(element.kind().as_key(), element.value())?;
+    }
+    Ok(dict)
+}
+
+/// Builds a list of `(kind, value, (start, end))` tuples in the order they appear in the name
+fn elements_to_list<'py>(
+    py: Python<'py>,
+    elements: &[Element<'_>],
+) -> PyResult<Bound<'py, PyList>> {
+    let items = elements
+        .iter()
+        .map(|e| {
+            let span = e.span();
+            PyTuple::new(
+                py,
+                [
+                    e.kind().as_key().into_pyobject(py)?.into_any(),
+                    e.value().into_pyobject(py)?.into_any(),
+                    (span.start, span.end).into_pyobject(py)?.into_any(),
+                ],
+            )
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    PyList::new(py, items)
+}
+
+/// Parses a name into a dict of its elements, e.g. `{"title": "Toradora!", "episode": "01"}`.
+///
+/// Parser options are given as keyword arguments, e.g. `parse(name, episode_title=False)`.
+#[pyfunction]
+#[pyo3(signature = (name, **options))]
+fn parse<'py>(
+    py: Python<'py>,
+    name: &str,
+    options: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = options_from_kwargs(options)?;
+    elements_to_dict(py, &crate::parse_with_options(name, options))
+}
+
+/// Parses a name into a list of `(kind, value, (start, end))` tuples.
+///
+/// Unlike `parse` this keeps every element, e.g. both episodes of `01-02`.
+#[pyfunction]
+#[pyo3(signature = (name, **options))]
+fn parse_elements<'py>(
+    py: Python<'py>,
+    name: &str,
+    options: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyList>> {
+    let options = options_from_kwargs(options)?;
+    elements_to_list(py, &crate::parse_with_options(name, options))
+}
+
+/// A reusable parser, which is faster when parsing many names with the same options.
+#[pyclass(name = "Parser", module = "anitomy")]
+struct PyParser {
+    parser: crate::Parser,
+}
+
+#[pymethods]
+impl PyParser {
+    #[new]
+    #[pyo3(signature = (**options))]
+    fn new(options: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        Ok(Self {
+            parser: crate::Parser::new(options_from_kwargs(options)?),
+        })
+    }
+
+    /// Parses a name into a dict of its elements.
+    fn parse<'py>(&mut self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyDict>> {
+        elements_to_dict(py, &self.parser.parse(name))
+    }
+
+    /// Parses a name into a list of `(kind, value, (start, end))` tuples.
+    fn parse_elements<'py>(&mut self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyList>> {
+        elements_to_list(py, &self.parser.parse(name))
+    }
+}
+
+#[pymodule]
+fn anitomy(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_elements, m)?)?;
+    m.add_class::<PyParser>()?;
+    Ok(())
+}
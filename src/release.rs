@@ -0,0 +1,488 @@
+use std::{borrow::Cow, ops::RangeInclusive};
+
+use crate::{
+    element::{Element, ElementKind},
+    rule::Rule,
+};
+
+/// An episode number, which can either be a whole number or a decimal one (e.g. `12.5` for recaps).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum EpisodeNumber {
+    Integer(u16),
+    Decimal(f32),
+}
+
+impl EpisodeNumber {
+    fn parse(s: &str) -> Option<Self> {
+        if let Ok(value) = s.parse::<u16>() {
+            return Some(Self::Integer(value));
+        }
+        let (whole, fraction) = s.split_once('.')?;
+        if whole.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        whole.parse::<u16>().ok()?;
+        s.parse::<f32>().ok().map(Self::Decimal)
+    }
+}
+
+/// A number that is either a single value or an inclusive range of values, e.g. `01-12`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Numbering<T> {
+    Single(T),
+    Range(RangeInclusive<T>),
+}
+
+impl<T: Copy> Numbering<T> {
+    /// Returns the first value, i.e. the start of the range or the single value.
+    pub fn first(&self) -> T {
+        match self {
+            Numbering::Single(value) => *value,
+            Numbering::Range(range) => *range.start(),
+        }
+    }
+
+    /// Returns the last value, i.e. the end of the range or the single value.
+    pub fn last(&self) -> T {
+        match self {
+            Numbering::Single(value) => *value,
+            Numbering::Range(range) => *range.end(),
+        }
+    }
+}
+
+/// An explicit range of episodes, e.g. `01v2-12v3`, `01 ~ 12` or `E01-E13`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpisodeRange {
+    pub start: EpisodeNumber,
+    pub end: EpisodeNumber,
+    /// The version of the first episode, e.g. `2` in `01v2-12`.
+    pub start_version: Option<u8>,
+    /// The version of the last episode, e.g. `3` in `01-12v3`.
+    pub end_version: Option<u8>,
+}
+
+/// A typed and structured view over the result of a parse.
+///
+/// This is built from the same [`Element`] objects that [`crate::parse`] returns, so the
+/// two views always agree with each other. Numeric values are parsed into their respective
+/// types, while values that have no numeric representation (e.g. Japanese numerals) are
+/// left out.
+///
+/// If multiple elements are found with the same [`ElementKind`], then the first one is stored.
+/// The exception is when two numbers come from the same token (e.g. `01-12`), in which case
+/// they are stored as a [`Numbering::Range`]. Ranges such as `01 ~ 12` whose ends are
+/// separate tokens are also stored as a range.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParsedRelease<'a> {
+    pub title: Option<Cow<'a, str>>,
+    pub episode_title: Option<Cow<'a, str>>,
+    pub release_group: Option<Cow<'a, str>>,
+    pub episode: Option<Numbering<EpisodeNumber>>,
+    pub episode_alt: Option<Numbering<EpisodeNumber>>,
+    pub season: Option<Numbering<u16>>,
+    pub volume: Option<Numbering<u16>>,
+    /// The total number of episodes, e.g. `12` in `03 of 12`.
+    pub episode_total: Option<u16>,
+    /// The total number of volumes, e.g. `5` in `Vol.03/05`.
+    pub volume_total: Option<u16>,
+    pub part: Option<u16>,
+    pub release_version: Option<u8>,
+    pub year: Option<u16>,
+    /// The date as a `(year, month, day)` tuple.
+    pub date: Option<(u16, u8, u8)>,
+    pub video_resolution: Option<Cow<'a, str>>,
+    pub file_checksum: Option<u32>,
+    pub file_extension: Option<Cow<'a, str>>,
+    /// The range of episodes along with the version of each end, if there is one.
+    pub episode_range: Option<EpisodeRange>,
+    /// Whether this is a batch of episodes, i.e. it has a range of at least three episodes
+    /// or a batch marker such as `Batch` or `Complete`, e.g. `(01-24 Complete)` or
+    /// `S01 [Batch]`. Double episodes such as `01-02` or `S01E01E02` are not a batch.
+    pub batch: bool,
+}
+
+fn parse_date(s: &str) -> Option<(u16, u8, u8)> {
+    let mut iter = s.split(['.', '-']);
+    let year = iter.next()?.parse().ok()?;
+    let month = iter.next()?.parse().ok()?;
+    let day = iter.next()?.parse().ok()?;
+    Some((year, month, day))
+}
+
+/// Stores a number into the slot, merging it into a range if it came from the same token.
+fn push_number<T: Copy>(
+    slot: &mut Option<Numbering<T>>,
+    last_position: &mut Option<usize>,
+    value: Option<T>,
+    position: usize,
+) {
+    let Some(value) = value else {
+        return;
+    };
+    match slot {
+        None => {
+            *slot = Some(Numbering::Single(value));
+            *last_position = Some(position);
+        }
+        Some(Numbering::Single(first)) if *last_position == Some(position) => {
+            *slot = Some(Numbering::Range(*first..=value));
+        }
+        Some(_) => {}
+    }
+}
+
+/// The number of episodes a range needs to be a batch, so that double episodes such as
+/// `01-02` are not a batch
+const MIN_BATCH_EPISODES: u16 = 3;
+
+/// Returns whether the value marks a batch of episodes, e.g. `Batch` or `Complete`
+fn is_batch_marker(value: &str) -> bool {
+    ["Batch", "Complete"]
+        .iter()
+        .any(|marker| value.eq_ignore_ascii_case(marker))
+}
+
+/// Returns whether the rule finds both ends of a range in separate tokens, e.g. `01 ~ 12`
+const fn is_separate_range(rule: Rule) -> bool {
+    matches!(rule, Rule::SeparatedEpisodeRange | Rule::SceneMultiEpisode)
+}
+
+/// Returns whether the episodes follow each other without a gap, e.g. `S01E01E02E03`
+fn is_consecutive(episodes: &[&EpisodeEntry]) -> bool {
+    episodes
+        .windows(2)
+        .all(|pair| match (pair[0].number, pair[1].number) {
+            (EpisodeNumber::Integer(a), EpisodeNumber::Integer(b)) => a.checked_add(1) == Some(b),
+            _ => false,
+        })
+}
+
+/// Returns whether the range has enough episodes to be a batch, see [`MIN_BATCH_EPISODES`]
+fn is_batch_range(range: &EpisodeRange) -> bool {
+    match (range.start, range.end) {
+        (EpisodeNumber::Integer(start), EpisodeNumber::Integer(end)) => {
+            end.saturating_sub(start) >= MIN_BATCH_EPISODES - 1
+        }
+        (start, end) => start < end,
+    }
+}
+
+/// An episode along with where it was found and its version
+struct EpisodeEntry {
+    number: EpisodeNumber,
+    position: usize,
+    rule: Rule,
+    version: Option<u8>,
+}
+
+#[derive(Default)]
+struct Builder<'a> {
+    release: ParsedRelease<'a>,
+    episode: Option<usize>,
+    episode_alt: Option<usize>,
+    season: Option<usize>,
+    volume: Option<usize>,
+    /// The episodes, which are checked for a range once every element is pushed
+    episodes: Vec<EpisodeEntry>,
+    seasons: usize,
+}
+
+impl<'a> Builder<'a> {
+    fn push(&mut self, kind: ElementKind, rule: Rule, value: Cow<'a, str>, position: usize) {
+        if kind == ElementKind::Episode {
+            if let Some(number) = EpisodeNumber::parse(&value) {
+                self.episodes.push(EpisodeEntry {
+                    number,
+                    position,
+                    rule,
+                    version: None,
+                });
+            }
+        }
+        if kind == ElementKind::Season {
+            self.seasons += 1;
+        }
+        // The version of each end of a range follows it, e.g. `01v2-12v3`
+        if kind == ElementKind::ReleaseVersion {
+            if let Some(episode) = self
+                .episodes
+                .last_mut()
+                .filter(|e| e.position == position && e.version.is_none())
+            {
+                episode.version = value.parse().ok();
+            }
+        }
+
+        let release = &mut self.release;
+        match kind {
+            ElementKind::Title => {
+                release.title.get_or_insert(value);
+            }
+            ElementKind::EpisodeTitle => {
+                release.episode_title.get_or_insert(value);
+            }
+            ElementKind::ReleaseGroup => {
+                release.release_group.get_or_insert(value);
+            }
+            ElementKind::VideoResolution => {
+                release.video_resolution.get_or_insert(value);
+            }
+            ElementKind::FileExtension => {
+                release.file_extension.get_or_insert(value);
+            }
+            ElementKind::Episode => push_number(
+                &mut release.episode,
+                &mut self.episode,
+                EpisodeNumber::parse(&value),
+                position,
+            ),
+            ElementKind::EpisodeAlt => push_number(
+                &mut release.episode_alt,
+                &mut self.episode_alt,
+                EpisodeNumber::parse(&value),
+                position,
+            ),
+            ElementKind::Season => push_number(
+                &mut release.season,
+                &mut self.season,
+                value.parse().ok(),
+                position,
+            ),
+            ElementKind::Volume => push_number(
+                &mut release.volume,
+                &mut self.volume,
+                value.parse().ok(),
+                position,
+            ),
+            ElementKind::EpisodeTotal if release.episode_total.is_none() => {
+                release.episode_total = value.parse().ok();
+            }
+            ElementKind::VolumeTotal if release.volume_total.is_none() => {
+                release.volume_total = value.parse().ok();
+            }
+            ElementKind::Part if release.part.is_none() => {
+                release.part = value.parse().ok();
+            }
+            ElementKind::ReleaseVersion if release.release_version.is_none() => {
+                release.release_version = value.parse().ok();
+            }
+            ElementKind::Year if release.year.is_none() => {
+                release.year = value.parse().ok();
+            }
+            ElementKind::Date if release.date.is_none() => {
+                release.date = parse_date(&value);
+            }
+            ElementKind::FileChecksum if release.file_checksum.is_none() => {
+                release.file_checksum = u32::from_str_radix(&value, 16).ok();
+            }
+            ElementKind::ReleaseInformation if is_batch_marker(&value) => {
+                release.batch = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> ParsedRelease<'a> {
+        let release = &mut self.release;
+        if let [start, second, ..] = self.episodes.as_slice() {
+            // Episodes listed in a scene name are only a range without a gap, e.g. `S01E01E02`
+            // but not `S01E01E03`, which is stored as its first episode
+            let listed: Vec<_> = self
+                .episodes
+                .iter()
+                .filter(|e| start.rule == Rule::SceneMultiEpisode && e.position == start.position)
+                .collect();
+            let end = match listed.as_slice() {
+                [_, .., last] => last,
+                _ => second,
+            };
+            let is_range = start.position == end.position
+                || (start.rule == end.rule && is_separate_range(start.rule));
+            if listed.len() > 1 && !is_consecutive(&listed) {
+                release.episode = Some(Numbering::Single(start.number));
+            } else if is_range && start.number < end.number {
+                release.episode = Some(Numbering::Range(start.number..=end.number));
+                release.episode_range = Some(EpisodeRange {
+                    start: start.number,
+                    end: end.number,
+                    start_version: start.version,
+                    end_version: end.version,
+                });
+            }
+        }
+        // Multiple seasons without any episode are a batch of seasons, e.g. `S01-S02`
+        let is_season_pack = self.seasons > 1 && release.episode.is_none();
+        let is_batch_range = release.episode_range.as_ref().is_some_and(is_batch_range);
+        release.batch |= is_batch_range || is_season_pack;
+        self.release
+    }
+}
+
+impl<'a, 'b: 'a> FromIterator<&'b Element<'a>> for ParsedRelease<'a> {
+    fn from_iter<T: IntoIterator<Item = &'b Element<'a>>>(iter: T) -> Self {
+        use std::borrow::Borrow;
+        let mut builder = Builder::default();
+        for element in iter {
+            builder.push(
+                element.kind,
+                element.rule,
+                Cow::Borrowed(element.value.borrow()),
+                element.position,
+            );
+        }
+        builder.finish()
+    }
+}
+
+impl<'a> FromIterator<Element<'a>> for ParsedRelease<'a> {
+    fn from_iter<T: IntoIterator<Item = Element<'a>>>(iter: T) -> Self {
+        let mut builder = Builder::default();
+        for element in iter {
+            builder.push(element.kind, element.rule, element.value, element.position);
+        }
+        builder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsed_release_toradora() {
+        let release = crate::parse_release(
+            "[TaigaSubs]_Toradora!_(2008)_-_01v2_-_Tiger_and_Dragon_[1280x720_H.264_FLAC][1234ABCD].mkv",
+        );
+        assert_eq!(release.title.as_deref(), Some("Toradora!"));
+        assert_eq!(release.release_group.as_deref(), Some("TaigaSubs"));
+        assert_eq!(
+            release.episode,
+            Some(Numbering::Single(EpisodeNumber::Integer(1)))
+        );
+        assert_eq!(release.release_version, Some(2));
+        assert_eq!(release.year, Some(2008));
+        assert_eq!(release.file_checksum, Some(0x1234ABCD));
+    }
+
+    #[test]
+    fn test_parsed_release_ranges() {
+        let release = crate::parse_release("[Group] Title - 01-12 [1080p].mkv");
+        assert_eq!(
+            release.episode,
+            Some(Numbering::Range(
+                EpisodeNumber::Integer(1)..=EpisodeNumber::Integer(12)
+            ))
+        );
+
+        let release = crate::parse_release("[Group] Title #01-02 [1080p].mkv");
+        assert_eq!(
+            release.episode,
+            Some(Numbering::Range(
+                EpisodeNumber::Integer(1)..=EpisodeNumber::Integer(2)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parsed_release_episode_range() {
+        let release = crate::parse_release("[Group] Title - 01v2-12v3 [1080p].mkv");
+        assert_eq!(
+            release.episode_range,
+            Some(EpisodeRange {
+                start: EpisodeNumber::Integer(1),
+                end: EpisodeNumber::Integer(12),
+                start_version: Some(2),
+                end_version: Some(3),
+            })
+        );
+        assert!(release.batch);
+
+        let release = crate::parse_release("[Group] Title E01-E13 [1080p].mkv");
+        assert_eq!(
+            release.episode,
+            Some(Numbering::Range(
+                EpisodeNumber::Integer(1)..=EpisodeNumber::Integer(13)
+            ))
+        );
+
+        let options = crate::Options::default().torrent(true);
+        let release = crate::parse_release_with_options("[Group] Title - 01 ~ 12 [1080p]", options);
+        assert_eq!(
+            release.episode_range.map(|r| (r.start, r.end)),
+            Some((EpisodeNumber::Integer(1), EpisodeNumber::Integer(12)))
+        );
+
+        // Two episodes from unrelated tokens aren't a range
+        let release = crate::parse_release("[Group] Title - 05 (03) [1080p].mkv");
+        assert_eq!(release.episode_range, None);
+        assert!(!release.batch);
+    }
+
+    #[test]
+    fn test_parsed_release_batch() {
+        assert!(crate::parse_release("[Group] Title (01-24 Complete) [1080p]").batch);
+        assert!(crate::parse_release("[Group] Title S01 [Batch][1080p]").batch);
+        assert!(crate::parse_release("[Group] Title S01-S02 [1080p]").batch);
+        assert!(!crate::parse_release("[Group] Title S01E05 [1080p].mkv").batch);
+
+        // Double episodes are a single release
+        assert!(!crate::parse_release("[Group] Title - 01-02 [1080p].mkv").batch);
+        assert!(!crate::parse_release("Title.S01E01E02.1080p.WEB-DL.H.264-GROUP").batch);
+
+        let range = EpisodeRange {
+            start: EpisodeNumber::Integer(0),
+            end: EpisodeNumber::Integer(u16::MAX),
+            start_version: None,
+            end_version: None,
+        };
+        assert!(is_batch_range(&range));
+    }
+
+    #[test]
+    fn test_parsed_release_scene_multi_episode() {
+        let release = crate::parse_release("Title.S01E01E02E03.1080p.WEB-DL.H.264-GROUP");
+        assert_eq!(
+            release.episode,
+            Some(Numbering::Range(
+                EpisodeNumber::Integer(1)..=EpisodeNumber::Integer(3)
+            ))
+        );
+
+        // Episodes with a gap aren't a range
+        let release = crate::parse_release("Title.S01E01E03.1080p.WEB-DL.H.264-GROUP");
+        assert_eq!(
+            release.episode,
+            Some(Numbering::Single(EpisodeNumber::Integer(1)))
+        );
+        assert_eq!(release.episode_range, None);
+
+        let release = crate::parse_release("Title.S01E01-E03.1080p.WEB-DL.H.264-GROUP");
+        assert_eq!(
+            release.episode_range.map(|r| (r.start, r.end)),
+            Some((EpisodeNumber::Integer(1), EpisodeNumber::Integer(3)))
+        );
+    }
+
+    #[test]
+    fn test_parsed_release_totals() {
+        let release = crate::parse_release("[Group] Title - 03 of 12 [1080p].mkv");
+        assert_eq!(release.episode_total, Some(12));
+
+        let release = crate::parse_release("[Group] Title Vol.03/05 [1080p].mkv");
+        assert_eq!(release.volume, Some(Numbering::Single(3)));
+        assert_eq!(release.volume_total, Some(5));
+    }
+
+    #[test]
+    fn test_parsed_release_decimal_and_date() {
+        let release = crate::parse_release("[Group] Title - 12.5 [720p].mkv");
+        assert_eq!(
+            release.episode,
+            Some(Numbering::Single(EpisodeNumber::Decimal(12.5)))
+        );
+
+        let release = crate::parse_release("Title 2019-05-12 [720p].mkv");
+        assert_eq!(release.date, Some((2019, 5, 12)));
+    }
+}
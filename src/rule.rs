@@ -0,0 +1,218 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// How reliable the result of a [`Rule`] is.
+///
+/// The tiers are ordered, so e.g. `confidence >= Confidence::Medium` can be used as a threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum Confidence {
+    /// A fallback heuristic that is often wrong, e.g. a bare `1080` or the last number
+    Low,
+    /// A positional heuristic that is usually right, e.g. the title or `Title - 01`
+    Medium,
+    /// An unambiguous pattern, e.g. a keyword, `S01E02` or a bracketed CRC32
+    High,
+}
+
+/// The rule of the parser that produced an element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum Rule {
+    /// A known file extension after the last dot, e.g. `.mkv`
+    FileExtension,
+    /// A keyword from the keyword set, e.g. `BluRay` or `FLAC`
+    Keyword,
+    /// A keyword that may also be part of a title and was not enclosed, e.g. `ESP`
+    AmbiguousKeyword,
+    /// An 8 digit hexadecimal number, e.g. `1234ABCD`
+    FileChecksum,
+    /// A video resolution pattern, e.g. `1080p` or `1920x1080`
+    VideoResolution,
+    /// A bare `1080` or `720` number when no other resolution was found
+    VideoResolutionNumber,
+    /// A `YYYY-MM-DD` date
+    Date,
+    /// A year enclosed in brackets, e.g. `(2008)`
+    EnclosedYear,
+    /// A year isolated by brackets and delimiters
+    IsolatedYear,
+    /// An ordinal number before a season keyword, e.g. `2nd Season`
+    SeasonOrdinal,
+    /// A number after a season keyword, e.g. `Season 2` or `Season II`
+    SeasonKeyword,
+    /// A range of seasons, e.g. `S01-S02`
+    SeasonRange,
+    /// A season prefixed with `S`, e.g. `S01`
+    SeasonPrefix,
+    /// A Japanese season counter, e.g. `第2期`
+    SeasonJapanese,
+    /// A number after a part keyword, e.g. `Part 2`
+    Part,
+    /// A number after a volume keyword, e.g. `Vol.01`
+    Volume,
+    /// A number after an episode keyword, e.g. `Episode 01`
+    EpisodeKeyword,
+    /// An episode prefixed with `E`, `EP` or `Eps`, e.g. `EP01`
+    EpisodePrefix,
+    /// A number followed by another number, e.g. `01 of 12` or `01 & 02`
+    NumberInNumber,
+    /// A number and the total number separated by a slash, e.g. `03/12`
+    NumberOfTotal,
+    /// An episode with a release version, e.g. `01v2`
+    EpisodeVersion,
+    /// A range of episodes in a single token, e.g. `01-12`
+    EpisodeRange,
+    /// A season and episode pair, e.g. `S01E02` or `1x02`
+    SeasonAndEpisode,
+    /// A number after a type keyword, e.g. `OVA 2`
+    EpisodeAfterType,
+    /// An episode prefixed with a number sign, e.g. `#01`
+    NumberSign,
+    /// A Japanese episode counter, e.g. `第01話`
+    EpisodeJapanese,
+    /// A Japanese count of every episode or volume, e.g. `全12話` or `全5巻`
+    TotalJapanese,
+    /// Two isolated numbers for the same episode, e.g. `01 (13)`
+    EpisodeEquivalent,
+    /// A range of episodes separated by spaces in a torrent title, e.g. `01 ~ 12`
+    SeparatedEpisodeRange,
+    /// A number after a dash, e.g. `Title - 01`
+    SeparatedNumber,
+    /// A fractional episode, e.g. `12.5`
+    FractionalEpisode,
+    /// A number enclosed in brackets, e.g. `[01]`
+    EnclosedNumber,
+    /// A partial episode, e.g. `12A`
+    PartialEpisode,
+    /// The last free number that is not part of the title
+    LastNumber,
+    /// The first run of free tokens
+    Title,
+    /// A title after a separator or in brackets after the title, e.g. `Attack on Titan` in
+    /// `Shingeki no Kyojin / Attack on Titan` or `進撃の巨人 (Attack on Titan)`
+    AlternativeTitle,
+    /// The first run of free enclosed tokens, e.g. `[Group]`
+    EnclosedReleaseGroup,
+    /// The last token after a dash, e.g. `-GROUP`
+    ReleaseGroupSuffix,
+    /// The run of free tokens after the episode
+    EpisodeTitle,
+    /// A free token that was not claimed by any other rule
+    Unknown,
+    /// The `-GROUP` suffix of a scene name, before a tracker tag such as `[rarbg]`
+    SceneReleaseGroup,
+    /// Multiple episodes of a scene name, e.g. `S01E01E02` or `S01E01-E02`
+    SceneMultiEpisode,
+    /// A year between the title and the rest of a scene name, e.g. `Title.2019.1080p`
+    SceneYear,
+    /// A value that was inferred from the other names of a batch, see
+    /// [`crate::parse_batch_with_options`]
+    BatchConsensus,
+}
+
+impl Rule {
+    /// Returns an identifier of the rule in snake case
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Rule::FileExtension => "file_extension",
+            Rule::Keyword => "keyword",
+            Rule::AmbiguousKeyword => "ambiguous_keyword",
+            Rule::FileChecksum => "file_checksum",
+            Rule::VideoResolution => "video_resolution",
+            Rule::VideoResolutionNumber => "video_resolution_number",
+            Rule::Date => "date",
+            Rule::EnclosedYear => "enclosed_year",
+            Rule::IsolatedYear => "isolated_year",
+            Rule::SeasonOrdinal => "season_ordinal",
+            Rule::SeasonKeyword => "season_keyword",
+            Rule::SeasonRange => "season_range",
+            Rule::SeasonPrefix => "season_prefix",
+            Rule::SeasonJapanese => "season_japanese",
+            Rule::Part => "part",
+            Rule::Volume => "volume",
+            Rule::EpisodeKeyword => "episode_keyword",
+            Rule::EpisodePrefix => "episode_prefix",
+            Rule::NumberInNumber => "number_in_number",
+            Rule::NumberOfTotal => "number_of_total",
+            Rule::EpisodeVersion => "episode_version",
+            Rule::EpisodeRange => "episode_range",
+            Rule::SeasonAndEpisode => "season_and_episode",
+            Rule::EpisodeAfterType => "episode_after_type",
+            Rule::NumberSign => "number_sign",
+            Rule::EpisodeJapanese => "episode_japanese",
+            Rule::TotalJapanese => "total_japanese",
+            Rule::EpisodeEquivalent => "episode_equivalent",
+            Rule::SeparatedEpisodeRange => "separated_episode_range",
+            Rule::SeparatedNumber => "separated_number",
+            Rule::FractionalEpisode => "fractional_episode",
+            Rule::EnclosedNumber => "enclosed_number",
+            Rule::PartialEpisode => "partial_episode",
+            Rule::LastNumber => "last_number",
+            Rule::Title => "title",
+            Rule::AlternativeTitle => "alternative_title",
+            Rule::EnclosedReleaseGroup => "enclosed_release_group",
+            Rule::ReleaseGroupSuffix => "release_group_suffix",
+            Rule::EpisodeTitle => "episode_title",
+            Rule::Unknown => "unknown",
+            Rule::SceneReleaseGroup => "scene_release_group",
+            Rule::SceneMultiEpisode => "scene_multi_episode",
+            Rule::SceneYear => "scene_year",
+            Rule::BatchConsensus => "batch_consensus",
+        }
+    }
+
+    /// Returns how reliable the results of this rule are
+    pub const fn confidence(&self) -> Confidence {
+        match self {
+            Rule::FileExtension
+            | Rule::Keyword
+            | Rule::FileChecksum
+            | Rule::VideoResolution
+            | Rule::Date
+            | Rule::EnclosedYear
+            | Rule::SeasonOrdinal
+            | Rule::SeasonKeyword
+            | Rule::SeasonRange
+            | Rule::SeasonPrefix
+            | Rule::SeasonJapanese
+            | Rule::Part
+            | Rule::Volume
+            | Rule::EpisodeKeyword
+            | Rule::EpisodePrefix
+            | Rule::EpisodeVersion
+            | Rule::SeasonAndEpisode
+            | Rule::NumberSign
+            | Rule::EpisodeJapanese
+            | Rule::TotalJapanese
+            | Rule::SceneReleaseGroup
+            | Rule::SceneMultiEpisode => Confidence::High,
+            Rule::IsolatedYear
+            | Rule::NumberInNumber
+            | Rule::NumberOfTotal
+            | Rule::EpisodeRange
+            | Rule::EpisodeAfterType
+            | Rule::EpisodeEquivalent
+            | Rule::SeparatedEpisodeRange
+            | Rule::SeparatedNumber
+            | Rule::FractionalEpisode
+            | Rule::EnclosedNumber
+            | Rule::Title
+            | Rule::AlternativeTitle
+            | Rule::EnclosedReleaseGroup
+            | Rule::EpisodeTitle
+            | Rule::SceneYear
+            | Rule::BatchConsensus => Confidence::Medium,
+            Rule::AmbiguousKeyword
+            | Rule::VideoResolutionNumber
+            | Rule::PartialEpisode
+            | Rule::LastNumber
+            | Rule::ReleaseGroupSuffix
+            | Rule::Unknown => Confidence::Low,
+        }
+    }
+}
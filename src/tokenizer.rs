@@ -1,29 +1,37 @@
+use std::ops::Range;
+
 use uncased::UncasedStr;
 
 use crate::{
-    keyword::{Keyword, KEYWORDS},
+    keyword::{Keyword, KeywordKind, KeywordSet},
     utils::get_pair_mut,
 };
 
+/// The kind of token that the tokenizer produced
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) enum TokenKind {
+pub enum TokenKind {
     OpenBracket,
     CloseBracket,
     Delimiter,
     Keyword,
     Text,
     Number,
-    Invalid,
 }
 
+/// A token from the input string.
+///
+/// Concatenating the values of every token returned by [`crate::tokenize`] reproduces
+/// the input string exactly.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct Token<'a> {
+pub struct Token<'a> {
     pub(crate) kind: TokenKind,
     pub(crate) value: &'a str,
     pub(crate) keyword: Option<Keyword>,
     pub(crate) unknown: bool,
     pub(crate) is_enclosed: bool,
+    pub(crate) depth: usize,
     pub(crate) position: usize,
+    pub(crate) span: Range<usize>,
 }
 
 impl<'a> Token<'a> {
@@ -34,7 +42,9 @@ impl<'a> Token<'a> {
             keyword: None,
             unknown: true,
             is_enclosed: false,
+            depth: 0,
             position: 0,
+            span: 0..0,
         }
     }
 
@@ -45,7 +55,9 @@ impl<'a> Token<'a> {
             keyword: None,
             unknown: true,
             is_enclosed: false,
+            depth: 0,
             position: 0,
+            span: 0..0,
         }
     }
 
@@ -56,7 +68,9 @@ impl<'a> Token<'a> {
             keyword: None,
             unknown: true,
             is_enclosed,
+            depth: 0,
             position: 0,
+            span: 0..0,
         }
     }
 
@@ -67,7 +81,9 @@ impl<'a> Token<'a> {
             keyword: None,
             unknown: true,
             is_enclosed,
+            depth: 0,
             position: 0,
+            span: 0..0,
         }
     }
 
@@ -78,17 +94,57 @@ impl<'a> Token<'a> {
             keyword: Some(keyword),
             unknown: true,
             is_enclosed,
+            depth: 0,
             position: 0,
+            span: 0..0,
         }
     }
 
+    /// Returns the kind of token
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    /// Returns the value of the token, which is a slice of the input string
+    pub fn value(&self) -> &'a str {
+        self.value
+    }
+
+    /// Returns the byte range of the token in the input string
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns a bool indicating whether the token is enclosed in brackets
+    pub fn is_enclosed(&self) -> bool {
+        self.is_enclosed
+    }
+
+    /// Returns the number of brackets that enclose the token.
+    ///
+    /// Brackets themselves have the depth of the brackets that enclose them.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the keyword that the token matched, if any
+    pub fn keyword(&self) -> Option<Keyword> {
+        self.keyword
+    }
+
+    /// Returns the kind of keyword that the token matched, if any
+    pub fn keyword_kind(&self) -> Option<KeywordKind> {
+        self.keyword.map(|k| k.kind)
+    }
+
     pub(crate) fn mark_known(&mut self) {
         self.unknown = false;
     }
 
-    fn with_enclosed(self, is_enclosed: bool) -> Self {
+    fn with_depth(self, depth: usize) -> Self {
         Self {
-            is_enclosed,
+            is_enclosed: depth > 0,
+            depth,
             ..self
         }
     }
@@ -254,13 +310,14 @@ fn is_keyword_boundary(s: &str) -> bool {
 }
 
 #[derive(Debug)]
-pub(crate) struct Tokenizer<'a> {
+pub(crate) struct Tokenizer<'a, 'k> {
     input: &'a str,
+    keywords: &'k KeywordSet,
 }
 
-impl<'a> Tokenizer<'a> {
-    pub(crate) fn new(s: &'a str) -> Self {
-        Self { input: s }
+impl<'a, 'k> Tokenizer<'a, 'k> {
+    pub(crate) fn with_keywords(s: &'a str, keywords: &'k KeywordSet) -> Self {
+        Self { input: s, keywords }
     }
 
     const fn is_empty(&self) -> bool {
@@ -268,27 +325,8 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn take_keyword(&mut self) -> Option<(&'a str, Keyword)> {
-        let mut key = "";
-        for (index, ch) in self.input.char_indices() {
-            let prefix = &self.input[0..(index + ch.len_utf8())];
-            if KEYWORDS.contains_key(UncasedStr::new(prefix)) {
-                key = prefix;
-            }
-            if KEYWORDS
-                .keys()
-                .filter(|key| key.starts_with(prefix))
-                .count()
-                > 0
-            {
-                continue;
-            }
-            if key.is_empty() {
-                return None;
-            }
-        }
-
+        let (key, keyword) = self.keywords.longest_prefix(self.input)?;
         let n = key.len();
-        let keyword = KEYWORDS.get(UncasedStr::new(key)).cloned()?;
         let rest = &self.input[n..];
         if keyword.is_bounded() && !is_keyword_boundary(rest) {
             // Allow things like "ED2" or "Season2"
@@ -342,10 +380,13 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
-    pub(crate) fn tokens(self) -> Vec<Token<'a>> {
+    /// Tokenizes the input into the given buffer, clearing it beforehand.
+    pub(crate) fn tokens_into(self, tokens: &mut Vec<Token<'a>>) {
         let original = self.input;
-        let mut tokens = self.into_iter().collect::<Vec<_>>();
+        tokens.clear();
+        tokens.extend(self);
         let mut start_length = 0;
+        let mut removed = vec![false; tokens.len()];
 
         // Fix up and combine some tokens (e.g. 1 '.' 2 => '1.2')
         for index in 0..tokens.len() {
@@ -355,8 +396,10 @@ impl<'a> Tokenizer<'a> {
             // e.g. 009-1 or 01+02
             if tokens[index].is_delimiter()
                 && tokens[index].value.starts_with(['.', '-', '&', '+', '~'])
+                && index > 0
+                && !removed[index - 1]
             {
-                if let Some((previous, next)) = get_pair_mut(&mut tokens, index - 1, index + 1) {
+                if let Some((previous, next)) = get_pair_mut(tokens, index - 1, index + 1) {
                     // Handle cases like No.N as well as 1.11
                     if (previous.is_mostly_numbers() && next.is_mostly_numbers())
                         || (is_dot
@@ -364,8 +407,8 @@ impl<'a> Tokenizer<'a> {
                             && previous.value == UncasedStr::new("No")
                             && next.is_number())
                     {
-                        previous.kind = TokenKind::Invalid;
-                        next.kind = TokenKind::Invalid;
+                        removed[index - 1] = true;
+                        removed[index + 1] = true;
                         let start = start_length - previous.value.len();
                         let end = start_length + token_length + next.value.len();
                         // Create a new combined token anchored by the middle delimiter
@@ -377,21 +420,24 @@ impl<'a> Tokenizer<'a> {
             start_length += token_length;
         }
 
-        tokens.retain(|t| t.kind != TokenKind::Invalid);
+        let mut removed = removed.into_iter();
+        tokens.retain(|_| !removed.next().unwrap_or_default());
         for (index, token) in tokens.iter_mut().enumerate() {
+            // Every token value is a slice of the original input
+            let start = token.value.as_ptr() as usize - original.as_ptr() as usize;
             token.position = index;
+            token.span = start..(start + token.value.len());
         }
-        tokens
     }
 }
 
-pub(crate) struct TokenIterator<'a> {
-    tokens: Tokenizer<'a>,
+pub(crate) struct TokenIterator<'a, 'k> {
+    tokens: Tokenizer<'a, 'k>,
     bracket_level: usize,
 }
 
-impl<'a> TokenIterator<'a> {
-    pub(crate) fn new(tokens: Tokenizer<'a>) -> Self {
+impl<'a, 'k> TokenIterator<'a, 'k> {
+    pub(crate) fn new(tokens: Tokenizer<'a, 'k>) -> Self {
         Self {
             tokens,
             bracket_level: 0,
@@ -399,17 +445,17 @@ impl<'a> TokenIterator<'a> {
     }
 }
 
-impl<'a> IntoIterator for Tokenizer<'a> {
+impl<'a, 'k> IntoIterator for Tokenizer<'a, 'k> {
     type Item = Token<'a>;
 
-    type IntoIter = TokenIterator<'a>;
+    type IntoIter = TokenIterator<'a, 'k>;
 
     fn into_iter(self) -> Self::IntoIter {
         TokenIterator::new(self)
     }
 }
 
-impl<'a> Iterator for TokenIterator<'a> {
+impl<'a> Iterator for TokenIterator<'a, '_> {
     type Item = Token<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -417,35 +463,38 @@ impl<'a> Iterator for TokenIterator<'a> {
             return None;
         }
 
-        let is_enclosed = self.bracket_level > 0;
+        let depth = self.bracket_level;
+        let is_enclosed = depth > 0;
 
         if let Some(value) = self.tokens.take_if(is_open_bracket) {
             self.bracket_level += 1;
-            return Some(Token::open_bracket(value).with_enclosed(self.bracket_level >= 2));
+            return Some(Token::open_bracket(value).with_depth(depth));
         }
 
         if let Some(value) = self.tokens.take_if(is_closed_bracket) {
-            self.bracket_level -= 1;
-            return Some(Token::close_bracket(value).with_enclosed(self.bracket_level >= 1));
+            // Unbalanced closing brackets are not enclosing anything
+            self.bracket_level = self.bracket_level.saturating_sub(1);
+            return Some(Token::close_bracket(value).with_depth(self.bracket_level));
         }
 
         if let Some(value) = self.tokens.take_if(is_delimiter) {
-            return Some(Token::delimiter(value, is_enclosed));
+            return Some(Token::delimiter(value, is_enclosed).with_depth(depth));
         }
 
-        match self.tokens.take_keyword() {
-            Some((value, keyword)) => Some(Token::from_keyword(value, keyword, is_enclosed)),
+        let token = match self.tokens.take_keyword() {
+            Some((value, keyword)) => Token::from_keyword(value, keyword, is_enclosed),
             None => {
                 let text = self.tokens.take_text();
                 if text.is_empty() {
-                    None
+                    return None;
                 } else if text.as_bytes().iter().all(u8::is_ascii_digit) {
-                    Some(Token::text(text, TokenKind::Number, is_enclosed))
+                    Token::text(text, TokenKind::Number, is_enclosed)
                 } else {
-                    Some(Token::text(text, TokenKind::Text, is_enclosed))
+                    Token::text(text, TokenKind::Text, is_enclosed)
                 }
             }
-        }
+        };
+        Some(token.with_depth(depth))
     }
 }
 
@@ -467,7 +516,7 @@ pub(crate) fn combine_tokens(tokens: &[Token<'_>], keep: KeepDelimiters) -> Stri
         .map(|f| delimiters.iter().all(|x| x == f))
         .unwrap_or(false);
     let has_spaces = delimiters.iter().copied().any(is_space);
-    let has_underscores = delimiters.iter().any(|&x| x == '_');
+    let has_underscores = delimiters.contains(&'_');
 
     let is_transformable_delimiter = |token: &Token<'_>| {
         if keep == KeepDelimiters::Yes {
@@ -542,7 +591,8 @@ mod tests {
     #[test]
     fn test_tokenizer_toradora() {
         let s = "[TaigaSubs]_Toradora!_(2008)_-_01v2_-_Tiger_and_Dragon_[1280x720_H.264_FLAC][1234ABCD]";
-        let tokenizer = Tokenizer::new(s);
+        let keywords = KeywordSet::new();
+        let tokenizer = Tokenizer::with_keywords(s, &keywords);
         #[rustfmt::skip]
         let expected = vec![
             Token::open_bracket("["),
@@ -588,7 +638,8 @@ mod tests {
     #[test]
     fn test_tokenizer_evangelion() {
         let s = "Evangelion_1.11_You_Are_(Not)_Alone_(2009)_[1080p,BluRay,x264,DTS-ES]_-_THORA.mkv";
-        let tokenizer = Tokenizer::new(s);
+        let keywords = KeywordSet::new();
+        let tokenizer = Tokenizer::with_keywords(s, &keywords);
         let expected = vec![
             Token::text("Evangelion", TokenKind::Text, false),
             Token::delimiter("_", false),
@@ -624,7 +675,8 @@ mod tests {
             Token::delimiter(".", false),
             Token::from_keyword("mkv", Keyword::new(KeywordKind::FileExtension), false),
         ];
-        let actual = tokenizer.tokens();
+        let mut actual = Vec::new();
+        tokenizer.tokens_into(&mut actual);
         for (original, expected) in actual.iter().zip(expected.iter()) {
             assert_eq!(original.kind, expected.kind);
             assert_eq!(original.value, expected.value);
@@ -0,0 +1,78 @@
+use crate::keyword::Keyword;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Sorted by byte so that lookups can use a binary search
+    children: Vec<(u8, u32)>,
+    keyword: Option<Keyword>,
+}
+
+/// A prefix trie of keywords over ASCII case-folded bytes.
+///
+/// This allows finding the longest keyword at the start of a string in a single pass,
+/// rather than checking every keyword against every prefix of the string.
+#[derive(Debug, Clone)]
+pub(crate) struct KeywordTrie {
+    nodes: Vec<Node>,
+}
+
+impl KeywordTrie {
+    pub(crate) fn new() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+        }
+    }
+
+    fn child(&self, node: usize, byte: u8) -> Option<usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|index| children[index].1 as usize)
+    }
+
+    pub(crate) fn insert(&mut self, key: &str, keyword: Keyword) {
+        let mut node = 0;
+        for byte in key.bytes().map(|b| b.to_ascii_lowercase()) {
+            node = match self.nodes[node]
+                .children
+                .binary_search_by_key(&byte, |&(b, _)| b)
+            {
+                Ok(index) => self.nodes[node].children[index].1 as usize,
+                Err(index) => {
+                    let next = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.insert(index, (byte, next as u32));
+                    next
+                }
+            };
+        }
+        self.nodes[node].keyword = Some(keyword);
+    }
+
+    /// Returns the length in bytes and the keyword of the longest key that the input starts with.
+    pub(crate) fn longest_prefix(&self, input: &str) -> Option<(usize, Keyword)> {
+        let mut node = 0;
+        let mut found = None;
+        for (index, byte) in input.bytes().enumerate() {
+            match self.child(node, byte.to_ascii_lowercase()) {
+                Some(next) => node = next,
+                None => break,
+            }
+            if let Some(keyword) = self.nodes[node].keyword {
+                found = Some((index + 1, keyword));
+            }
+        }
+        found
+    }
+}
+
+impl<'a> FromIterator<(&'a str, Keyword)> for KeywordTrie {
+    fn from_iter<T: IntoIterator<Item = (&'a str, Keyword)>>(iter: T) -> Self {
+        let mut trie = Self::new();
+        for (key, keyword) in iter {
+            trie.insert(key, keyword);
+        }
+        trie
+    }
+}
@@ -76,12 +76,21 @@ pub(crate) fn last_chunk_mut<const N: usize, T>(slice: &mut [T]) -> Option<&mut
 //     None
 // }
 
+/// Reuses the allocation of an empty vector for a vector of another type with the same layout.
+///
+/// This is mainly used to keep a buffer of tokens around without tying it to the lifetime
+/// of the input that was tokenized.
+pub(crate) fn recycle_vec<T, U>(mut vec: Vec<T>) -> Vec<U> {
+    vec.clear();
+    vec.into_iter().map(|_| unreachable!()).collect()
+}
+
 /// Returns a pair of disjoint mutable references to the slice at index `i` and `j`.
 ///
 /// `i` must be smaller than `j` and `j` must be smaller than the length of the slice,
 /// otherwise `None` is returned.
 pub(crate) fn get_pair_mut<T>(slice: &mut [T], i: usize, j: usize) -> Option<(&mut T, &mut T)> {
-    if i >= j || j > slice.len() {
+    if i >= j || j >= slice.len() {
         None
     } else {
         let (left, right) = slice.split_at_mut(j);
@@ -0,0 +1,268 @@
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::{options::JsOptions, Element, ElementKind, Parser};
+
+// Only the WASM target keeps the custom section, the types are checked by the tests otherwise
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+const TS_TYPES: &str = r#"
+/** A single value, or every value in order of appearance if a kind was found multiple times. */
+export type ElementValue = string | string[];
+
+/** The parsed elements keyed by their kind, only the kinds that were found are present. */
+export interface ElementObject {
+  audio_term?: ElementValue;
+  device_compatibility?: ElementValue;
+  episode?: ElementValue;
+  episode_alt?: ElementValue;
+  episode_title?: ElementValue;
+  file_checksum?: ElementValue;
+  file_extension?: ElementValue;
+  language?: ElementValue;
+  other?: ElementValue;
+  release_group?: ElementValue;
+  release_information?: ElementValue;
+  release_version?: ElementValue;
+  season?: ElementValue;
+  source?: ElementValue;
+  subtitles?: ElementValue;
+  title?: ElementValue;
+  type?: ElementValue;
+  video_resolution?: ElementValue;
+  video_term?: ElementValue;
+  volume?: ElementValue;
+  part?: ElementValue;
+  year?: ElementValue;
+  date?: ElementValue;
+  unknown?: ElementValue;
+  alternative_title?: ElementValue;
+  episode_total?: ElementValue;
+  volume_total?: ElementValue;
+}
+
+/** The key of an `ElementKind` in `ElementObject`, see `elementKey`. */
+export type ElementKey =
+  | "audio_term"
+  | "device_compatibility"
+  | "episode"
+  | "episode_alt"
+  | "episode_title"
+  | "file_checksum"
+  | "file_extension"
+  | "language"
+  | "other"
+  | "release_group"
+  | "release_information"
+  | "release_version"
+  | "season"
+  | "source"
+  | "subtitles"
+  | "title"
+  | "type"
+  | "video_resolution"
+  | "video_term"
+  | "volume"
+  | "part"
+  | "year"
+  | "date"
+  | "unknown"
+  | "alternative_title"
+  | "episode_total"
+  | "volume_total";
+
+/** The toggles of `Options` as a plain object, a missing toggle keeps its default. */
+export interface OptionsObject {
+  episode?: boolean;
+  episode_title?: boolean;
+  file_checksum?: boolean;
+  file_extension?: boolean;
+  release_group?: boolean;
+  season?: boolean;
+  title?: boolean;
+  video_resolution?: boolean;
+  year?: boolean;
+  date?: boolean;
+  unknown?: boolean;
+  scene?: boolean;
+  torrent?: boolean;
+}
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_APPEND_CONTENT: &'static str = TS_TYPES;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "ElementObject")]
+    pub type JsElementObject;
+
+    #[wasm_bindgen(typescript_type = "ElementObject[]")]
+    pub type JsElementObjectArray;
+
+    #[wasm_bindgen(typescript_type = "ElementKey")]
+    pub type JsElementKey;
+
+    #[wasm_bindgen(typescript_type = "OptionsObject")]
+    pub type JsOptionsObject;
+}
+
+/// Returns the key of an element kind in an `ElementObject`, e.g. `release_group`
+#[wasm_bindgen(js_name = elementKey)]
+pub fn element_key(kind: ElementKind) -> JsElementKey {
+    JsValue::from_str(kind.as_key()).unchecked_into()
+}
+
+/// Creates options from a plain object of toggles, e.g. `{ episode_title: false }`
+#[wasm_bindgen(js_name = optionsFromObject)]
+pub fn options_from_object(object: &JsOptionsObject) -> JsOptions {
+    let mut options = JsOptions::new();
+    let fields = [
+        ("episode", &mut options.episode),
+        ("episode_title", &mut options.episode_title),
+        ("file_checksum", &mut options.file_checksum),
+        ("file_extension", &mut options.file_extension),
+        ("release_group", &mut options.release_group),
+        ("season", &mut options.season),
+        ("title", &mut options.title),
+        ("video_resolution", &mut options.video_resolution),
+        ("year", &mut options.year),
+        ("date", &mut options.date),
+        ("unknown", &mut options.unknown),
+        ("scene", &mut options.scene),
+        ("torrent", &mut options.torrent),
+    ];
+    for (key, field) in fields {
+        let value = Reflect::get(object, &JsValue::from_str(key)).unwrap_throw();
+        if let Some(value) = value.as_bool() {
+            *field = value;
+        }
+    }
+    options
+}
+
+/// Builds a plain object keyed by [`crate::ElementKind::as_key`].
+///
+/// Unlike [`crate::ElementObject`] no values are lost, a kind that was found multiple times
+/// (e.g. both episodes of `01-02`) has an array of values instead.
+fn to_object(elements: &[Element<'_>]) -> JsElementObject {
+    let object = Object::new();
+    for element in elements {
+        let key = JsValue::from_str(element.kind.as_key());
+        let value = JsValue::from_str(&element.value);
+        let existing = Reflect::get(&object, &key).unwrap_throw();
+        let value = if existing.is_undefined() {
+            value
+        } else if let Some(array) = existing.dyn_ref::<Array>() {
+            array.push(&value);
+            continue;
+        } else {
+            Array::of2(&existing, &value).into()
+        };
+        Reflect::set(&object, &key, &value).unwrap_throw();
+    }
+    object.unchecked_into()
+}
+
+/// Parses a string into a plain object of its elements
+#[wasm_bindgen(js_name = parseObject)]
+pub fn parse_object(input: &str, options: &JsOptions) -> JsElementObject {
+    to_object(&Parser::new((*options).into()).parse(input))
+}
+
+/// Parses multiple strings into plain objects of their elements, in the same order
+#[wasm_bindgen(js_name = parseMany)]
+pub fn parse_many(inputs: Vec<String>, options: &JsOptions) -> JsElementObjectArray {
+    let mut parser = Parser::new((*options).into());
+    let mut elements = Vec::new();
+    let array = Array::new_with_length(inputs.len() as u32);
+    for (index, input) in inputs.iter().enumerate() {
+        parser.parse_into(input, &mut elements);
+        array.set(index as u32, to_object(&elements).into());
+    }
+    array.unchecked_into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the keys listed on the indented lines after `start`
+    fn declared_keys(start: &str) -> Vec<&'static str> {
+        TS_TYPES
+            .lines()
+            .skip_while(|line| *line != start)
+            .skip(1)
+            .take_while(|line| line.starts_with("  "))
+            .map(|line| {
+                line.trim_start_matches([' ', '|'])
+                    .trim_end_matches(';')
+                    .trim_matches('"')
+                    .split('?')
+                    .next()
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_utf16_spans() {
+        let input = "[Gröup] 進撃の巨人 🎉 - 01";
+        let elements: Vec<_> = crate::parse(input)
+            .into_iter()
+            .map(|e| crate::element::JsElement::new(input, e))
+            .collect();
+        let utf16: Vec<u16> = input.encode_utf16().collect();
+        for element in &elements {
+            let slice = String::from_utf16(&utf16[element.start..element.end]).unwrap();
+            assert_eq!(slice, element.value);
+        }
+        let episode = elements.iter().find(|e| e.kind == ElementKind::Episode);
+        assert_eq!(episode.map(|e| (e.start, e.end)), Some((19, 21)));
+    }
+
+    #[test]
+    fn test_typescript_types() {
+        let object_keys = declared_keys("export interface ElementObject {");
+        assert_eq!(object_keys.len(), 27);
+        for key in &object_keys {
+            let kind = ElementKind::from_key(key).unwrap();
+            assert_eq!(kind.as_key(), *key);
+        }
+        assert_eq!(declared_keys("export type ElementKey ="), object_keys);
+
+        // A new field fails to compile here, so it can't be missed in `OptionsObject`
+        let JsOptions {
+            episode: _,
+            episode_title: _,
+            file_checksum: _,
+            file_extension: _,
+            release_group: _,
+            season: _,
+            title: _,
+            video_resolution: _,
+            year: _,
+            date: _,
+            unknown: _,
+            scene: _,
+            torrent: _,
+        } = JsOptions::new();
+        assert_eq!(
+            declared_keys("export interface OptionsObject {"),
+            [
+                "episode",
+                "episode_title",
+                "file_checksum",
+                "file_extension",
+                "release_group",
+                "season",
+                "title",
+                "video_resolution",
+                "year",
+                "date",
+                "unknown",
+                "scene",
+                "torrent",
+            ]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use std::ffi::CStr;
+
+use anitomy::capi::*;
+
+fn parse(
+    input: &str,
+    options: *const AnitomyOptions,
+) -> Vec<(AnitomyElementKind, String, usize, usize)> {
+    unsafe {
+        let elements = anitomy_parse(input.as_ptr().cast(), input.len(), options);
+        assert!(!elements.is_null());
+        let mut result = Vec::new();
+        for index in 0..anitomy_elements_len(elements) {
+            let mut element = std::mem::MaybeUninit::<AnitomyElement>::uninit();
+            assert!(anitomy_elements_get(elements, index, element.as_mut_ptr()));
+            let element = element.assume_init();
+            let value = CStr::from_ptr(element.value).to_str().unwrap().to_owned();
+            assert_eq!(value.len(), element.value_len);
+            result.push((element.kind, value, element.span_start, element.span_end));
+        }
+        let mut element = std::mem::MaybeUninit::<AnitomyElement>::uninit();
+        assert!(!anitomy_elements_get(
+            elements,
+            result.len(),
+            element.as_mut_ptr()
+        ));
+        anitomy_elements_free(elements);
+        result
+    }
+}
+
+#[test]
+fn test_capi_parse() {
+    let input = "[TaigaSubs]_Toradora!_(2008)_-_01v2_-_Tiger_and_Dragon_[1280x720_H.264_FLAC][1234ABCD].mkv";
+    let elements = parse(input, std::ptr::null());
+    let expected: Vec<_> = anitomy::parse(input)
+        .into_iter()
+        .map(|e| {
+            let span = e.span();
+            (e.kind().into(), e.value().to_owned(), span.start, span.end)
+        })
+        .collect();
+    assert_eq!(elements, expected);
+    assert!(elements.contains(&(
+        AnitomyElementKind::ReleaseGroup,
+        "TaigaSubs".to_owned(),
+        1,
+        10
+    )));
+
+    let name = anitomy_element_kind_name(AnitomyElementKind::ReleaseGroup as u32);
+    assert_eq!(
+        unsafe { CStr::from_ptr(name) }.to_str(),
+        Ok("Release Group")
+    );
+    assert!(anitomy_element_kind_name(u32::MAX).is_null());
+}
+
+#[test]
+fn test_capi_options() {
+    let input = "[Group] Title - 01 - Episode Title [FOO].mkv";
+    unsafe {
+        let options = anitomy_options_new();
+        assert!(anitomy_options_set(
+            options,
+            AnitomyOption::EpisodeTitle as u32,
+            false
+        ));
+        assert!(anitomy_options_set(
+            options,
+            AnitomyOption::UnknownTokens as u32,
+            true
+        ));
+        assert!(!anitomy_options_set(options, 1000, true));
+        assert!(!anitomy_options_set(
+            std::ptr::null_mut(),
+            AnitomyOption::Episode as u32,
+            true
+        ));
+        let kinds: Vec<_> = parse(input, options).into_iter().map(|e| e.0).collect();
+        anitomy_options_free(options);
+        assert!(!kinds.contains(&AnitomyElementKind::EpisodeTitle));
+        assert!(kinds.contains(&AnitomyElementKind::Unknown));
+
+        assert!(anitomy_parse([0xffu8].as_ptr().cast(), 1, std::ptr::null()).is_null());
+        assert!(anitomy_parse(std::ptr::null(), 0, std::ptr::null()).is_null());
+        assert_eq!(anitomy_elements_len(std::ptr::null()), 0);
+        anitomy_elements_free(std::ptr::null_mut());
+        anitomy_options_free(std::ptr::null_mut());
+    }
+}
+
+#[test]
+fn test_capi_header() {
+    // The header is generated by cbindgen, so this catches it being out of date
+    let header = include_str!("../include/anitomy.h");
+    assert!(header.contains(
+        "bool anitomy_options_set(struct AnitomyOptions *options, uint32_t option, bool enabled);"
+    ));
+    assert!(header.contains("const char *anitomy_element_kind_name(uint32_t kind);"));
+
+    let mut kind = 0;
+    while let Ok(value) = AnitomyElementKind::try_from(kind) {
+        let name = unsafe { CStr::from_ptr(anitomy_element_kind_name(kind)) };
+        let name = name.to_str().unwrap().to_uppercase().replace(' ', "_");
+        assert!(header.contains(&format!("ANITOMY_ELEMENT_KIND_{name} = {kind},")));
+        assert_eq!(value as u32, kind);
+        kind += 1;
+    }
+    assert!(kind > 0);
+    let mut option = 0;
+    while AnitomyOption::try_from(option).is_ok() {
+        option += 1;
+    }
+    assert_eq!(
+        header.matches("\n  ANITOMY_OPTION_").count(),
+        option as usize
+    );
+}
@@ -0,0 +1,182 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+fn anitomy(args: &[&str], stdin: &str) -> (bool, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_anitomy"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("could not run the binary");
+    // The binary might exit before reading stdin, e.g. on invalid arguments
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn test_cli_formats() {
+    let input = "[TaigaSubs]_Toradora!_(2008)_-_01v2_-_Tiger_and_Dragon_[1280x720_H.264_FLAC][1234ABCD].mkv";
+
+    let (success, table) = anitomy(&[input], "");
+    assert!(success);
+    assert!(table.starts_with(input));
+    assert!(table.contains("  Release Group     TaigaSubs\n"));
+
+    let (success, json) = anitomy(&["--format", "json", input, "86 - 01"], "");
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json[0]["input"], input);
+    assert_eq!(json[0]["title"], "Toradora!");
+    assert_eq!(json[0]["file_checksum"], "1234ABCD");
+    assert_eq!(json[1]["episode"], "01");
+
+    let (success, csv) = anitomy(&["-f", "csv"], "a, \"b\" - 01.mkv\n\nTitle S01E02\n");
+    assert!(success);
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("input,audio_term,"));
+    assert!(lines[1].starts_with("\"a, \"\"b\"\" - 01.mkv\",,,01,"));
+
+    // Kinds found multiple times keep every value
+    let input = "[Group] Show - 01-02 [English][Spanish].mkv";
+    let (success, json) = anitomy(&["-f", "json", input], "");
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json[0]["episode"], serde_json::json!(["01", "02"]));
+    assert_eq!(
+        json[0]["language"],
+        serde_json::json!(["English", "Spanish"])
+    );
+    let (success, csv) = anitomy(&["-f", "csv", input], "");
+    assert!(success);
+    let row = csv.lines().nth(1).unwrap();
+    assert!(row.contains(",01;02,"), "{row}");
+    assert!(row.contains(",English;Spanish,"), "{row}");
+}
+
+#[test]
+fn test_cli_options() {
+    let (success, jsonl) = anitomy(&["-f=jsonl"], "Title - 01.mkv\r\nTitle - 02 (2019).mkv\n");
+    assert!(!success, "`-f=jsonl` is not a valid short flag");
+    assert!(jsonl.is_empty());
+
+    let (success, jsonl) = anitomy(
+        &["--format=jsonl", "--no-file-extension", "--no-year"],
+        "Title - 01.mkv\r\nTitle - 02 (2019).mkv\n",
+    );
+    assert!(success);
+    let lines: Vec<serde_json::Value> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["input"], "Title - 01.mkv");
+    assert!(lines[0].get("file_extension").is_none());
+    assert!(lines[1].get("year").is_none());
+
+    let (success, jsonl) = anitomy(
+        &[
+            "-f",
+            "jsonl",
+            "--unknown-tokens",
+            "[Group] Title - 01 [FOO]",
+        ],
+        "",
+    );
+    assert!(success);
+    assert!(jsonl.contains(r#""unknown":"FOO""#));
+
+    assert!(!anitomy(&["--no-such-option"], "").0);
+    assert!(!anitomy(&["--episode=false", "Title - 01"], "").0);
+    assert!(!anitomy(&["--help=yes"], "").0);
+    assert!(!anitomy(&["organize", "--dry-run=no", "a", "b"], "").0);
+    assert!(!anitomy(&["--format", "xml"], "").0);
+    assert!(anitomy(&["--help"], "").1.starts_with("Usage: anitomy"));
+
+    // Names of commands can still be parsed
+    let (success, json) = anitomy(&["parse", "-f", "json", "organize"], "");
+    assert!(success);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap()[0]["title"],
+        "organize"
+    );
+    let (success, json) = anitomy(&["-f", "json", "--", "undo", "-Title"], "");
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json[0]["title"], "undo");
+    assert_eq!(json[1]["input"], "-Title");
+}
+
+#[test]
+fn test_cli_directory() {
+    let dir = std::env::temp_dir().join(format!("anitomy-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("[Group] Title - 02.mkv"), "").unwrap();
+    std::fs::write(dir.join("[Group] Title - 01.mkv"), "").unwrap();
+
+    let (success, jsonl) = anitomy(&["-f", "jsonl", "--dir", dir.to_str().unwrap()], "");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(success);
+    let episodes: Vec<_> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["episode"].clone())
+        .collect();
+    assert_eq!(episodes, ["01", "02"]);
+}
+
+#[test]
+fn test_cli_organize() {
+    let dir = std::env::temp_dir().join(format!("anitomy-cli-organize-{}", std::process::id()));
+    let source = dir.join("downloads");
+    let target = dir.join("library");
+    std::fs::create_dir_all(&source).unwrap();
+    std::fs::write(source.join("[Group] Title - 01 [1080p].mkv"), "").unwrap();
+    std::fs::write(source.join("[Group] Title Movie (2019) [1080p].mkv"), "").unwrap();
+    let (source_arg, target_arg) = (source.to_str().unwrap(), target.to_str().unwrap());
+
+    let (success, plan) = anitomy(
+        &["organize", "-n", "-p", "kodi", source_arg, target_arg],
+        "",
+    );
+    assert!(success);
+    assert!(plan.contains("Title S01E01.mkv"), "{plan}");
+    assert!(!target.exists());
+
+    let (success, _) = anitomy(
+        &["organize", "--action=hardlink", source_arg, target_arg],
+        "",
+    );
+    assert!(success);
+    let episode = target.join("Title/Season 01/Title - s01e01.mkv");
+    assert!(episode.is_file());
+    assert!(source.join("[Group] Title - 01 [1080p].mkv").is_file());
+
+    // Everything is already in place, so organizing again only finds conflicts
+    let (success, plan) = anitomy(&["organize", "-a", "hardlink", source_arg, target_arg], "");
+    assert!(!success);
+    assert!(plan.contains("conflict: "), "{plan}");
+
+    // Every run has its own journal
+    let journals: Vec<_> = std::fs::read_dir(&target)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "tsv"))
+        .collect();
+    assert_eq!(journals.len(), 1);
+    let journal = &journals[0];
+    let (success, _) = anitomy(&["undo", journal.to_str().unwrap()], "");
+    assert!(success);
+    assert!(!episode.exists());
+    assert!(!journal.exists());
+
+    assert!(!anitomy(&["organize", source_arg], "").0);
+    assert!(!anitomy(&["organize", "-p", "vlc", source_arg, target_arg], "").0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use anitomy::ElementKind;
+use anitomy::{ElementKind, Options, Rule};
 use serde::{
     de::{value::SeqAccessDeserializer, Visitor},
     Deserialize,
@@ -58,6 +58,7 @@ impl<'de> Deserialize<'de> for TestValue {
     }
 }
 
+#[allow(unused_macros)]
 macro_rules! trace_variables {
     ($($name:ident,)* $block:block, $skip:expr) => {
         let variables = [
@@ -159,10 +160,13 @@ fn make_test_map(values: Vec<anitomy::Element<'_>>) -> HashMap<ElementKind, Test
     result
 }
 
+fn test_data<T: serde::de::DeserializeOwned>() -> Vec<T> {
+    serde_json::from_str(include_str!("data.json")).expect("could not parse JSON")
+}
+
 #[test]
 fn test_json_data() {
-    let data = include_str!("data.json");
-    let tests: Vec<InputData> = serde_json::from_str(data).expect("could not parse JSON");
+    let tests: Vec<InputData> = test_data();
     let total = tests.len();
 
     let mut passed = 0;
@@ -224,3 +228,942 @@ fn test_json_data() {
 
     assert!(pass_rate > 0.0, "No tests passed!");
 }
+
+#[test]
+fn test_element_spans() {
+    let tests: Vec<InputData> = test_data();
+
+    for test in tests {
+        let input = test.input.as_str();
+        for element in anitomy::parse_with_options(input, test.options.into()) {
+            let span = element.span();
+            let source = input
+                .get(span.clone())
+                .unwrap_or_else(|| panic!("invalid span {span:?} for {element:?} in {input:?}"));
+            match element.kind() {
+                ElementKind::FileChecksum | ElementKind::FileExtension | ElementKind::Episode => {
+                    assert_eq!(source, element.value(), "input: {input:?}")
+                }
+                ElementKind::Title | ElementKind::EpisodeTitle => {
+                    assert!(!source.is_empty(), "input: {input:?}")
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn test_custom_keywords() {
+    use anitomy::{Keyword, KeywordKind, KeywordSet};
+
+    let input = "[Group] Title - 01 [ADN][1080p].mkv";
+    let mut keywords = KeywordSet::new();
+    assert!(keywords.get("ADN").is_none());
+    assert_eq!(
+        keywords.insert("ADN", Keyword::new(KeywordKind::Source)),
+        None
+    );
+    let mut parser = anitomy::Parser::default().with_keywords(keywords);
+    let elements = parser.parse(input);
+    assert!(elements
+        .iter()
+        .any(|e| e.kind() == ElementKind::Source && e.value() == "ADN"));
+
+    // Built-in keywords can be removed and overridden as well
+    let keywords = parser.keywords_mut();
+    assert_eq!(
+        keywords.remove("1080p"),
+        Some(Keyword::unbounded(KeywordKind::VideoResolution))
+    );
+    assert!(!keywords.contains("1080P"));
+    keywords.insert("Title", Keyword::ambiguous(KeywordKind::Other));
+    assert_eq!(
+        keywords.get("title").map(|k| k.kind()),
+        Some(KeywordKind::Other)
+    );
+    assert!(keywords.get("title").is_some_and(|k| k.is_ambiguous()));
+}
+
+#[test]
+fn test_reusable_parser() {
+    let tests: Vec<InputData> = test_data();
+
+    let mut parser = anitomy::Parser::default();
+    let mut results = Vec::new();
+    for test in &tests {
+        let options = test.options.clone().into();
+        *parser.options_mut() = options;
+        parser.parse_into(&test.input, &mut results);
+        assert_eq!(results, anitomy::parse_with_options(&test.input, options));
+    }
+}
+
+#[test]
+fn test_keyword_longest_prefix() {
+    let tests: Vec<InputData> = test_data();
+
+    let keywords = anitomy::KeywordSet::new();
+    let keys = keywords.iter().collect::<Vec<_>>();
+    for test in &tests {
+        for (index, _) in test.input.char_indices() {
+            let input = &test.input[index..];
+            // The longest key that the input starts with, ignoring ASCII case
+            let expected = keys
+                .iter()
+                .filter(|(key, _)| {
+                    input
+                        .get(..key.len())
+                        .is_some_and(|s| s.eq_ignore_ascii_case(key))
+                })
+                .max_by_key(|(key, _)| key.len())
+                .map(|(key, keyword)| (&input[..key.len()], *keyword));
+            assert_eq!(keywords.longest_prefix(input), expected, "input: {input:?}");
+        }
+    }
+}
+
+#[test]
+fn test_tokens_are_lossless() {
+    let tests: Vec<InputData> = test_data();
+    let extra = [
+        "-Title",
+        "Title -",
+        ".hack//Sign - 01",
+        "Title) - 01 (",
+        "01-02-03 ~ 04.5",
+        "No.6 - 01",
+    ];
+
+    for input in tests.iter().map(|t| t.input.as_str()).chain(extra) {
+        let tokens = anitomy::tokenize(input);
+        let combined = tokens.iter().map(|t| t.value()).collect::<String>();
+        assert_eq!(combined, input);
+
+        let mut offset = 0;
+        for token in &tokens {
+            assert_eq!(token.span(), offset..(offset + token.value().len()));
+            assert_eq!(token.is_enclosed(), token.depth() > 0);
+            offset = token.span().end;
+        }
+        // Make sure nothing panics on odd inputs either
+        anitomy::parse(input);
+    }
+}
+
+#[test]
+fn test_explained_parse() {
+    use anitomy::Rule;
+
+    let tests: Vec<InputData> = test_data();
+    for test in &tests {
+        let options = test.options.clone().into();
+        let explanation = anitomy::parse_explained(&test.input, options);
+        let elements: Vec<_> = explanation.elements().cloned().collect();
+        assert_eq!(elements, anitomy::parse_with_options(&test.input, options));
+        for explained in &explanation.elements {
+            assert!(!explained.tokens.is_empty(), "{}", test.input);
+            assert!(!explanation.rejected.contains(&explained.rule()));
+        }
+    }
+
+    let explanation =
+        anitomy::parse_explained("Title Season 2 - 第05話 [720p]", Default::default());
+    let rules: Vec<_> = explanation
+        .elements
+        .iter()
+        .map(|e| (e.element.kind(), e.rule()))
+        .collect();
+    assert_eq!(
+        rules,
+        [
+            (ElementKind::Title, Rule::Title),
+            (ElementKind::Season, Rule::SeasonKeyword),
+            (ElementKind::Episode, Rule::EpisodeJapanese),
+            (ElementKind::VideoResolution, Rule::Keyword),
+        ]
+    );
+    let season: Vec<_> = explanation.elements[1]
+        .tokens
+        .iter()
+        .map(|t| t.value())
+        .collect();
+    assert_eq!(season, ["Season", " ", "2"]);
+    assert!(explanation.rejected.contains(&Rule::EpisodePrefix));
+    assert!(!explanation.rejected.contains(&Rule::LastNumber));
+
+    // A number and the total is only tried once every other episode rule was
+    let explanation = anitomy::parse_explained("[Group] Title - 03/12 [1080p]", Default::default());
+    let episode = explanation
+        .elements
+        .iter()
+        .find(|e| e.element.kind() == ElementKind::Episode)
+        .unwrap();
+    assert_eq!(episode.rule(), Rule::NumberOfTotal);
+    assert!(explanation.rejected.contains(&Rule::SeparatedNumber));
+    assert!(explanation.rejected.contains(&Rule::LastNumber));
+    assert!(!explanation.rejected.contains(&Rule::NumberOfTotal));
+}
+
+#[test]
+fn test_episode_title_delimiters() {
+    let episode_title = |input: &str| {
+        anitomy::parse(input)
+            .into_iter()
+            .find(|e| e.kind() == ElementKind::EpisodeTitle)
+            .map(|e| e.value().to_owned())
+    };
+
+    // Only the delimiter before the file extension is dropped
+    assert_eq!(
+        episode_title("Title - 05 - Episode Title_.mkv").as_deref(),
+        Some("Episode Title")
+    );
+    assert_eq!(
+        episode_title("[NinjaPanda] Tiger & Bunny #01 All's well that ends well. (v3, 1080p Hi10P, DA AAC) [4A9AB85F].mkv").as_deref(),
+        Some("All's well that ends well.")
+    );
+}
+
+#[test]
+fn test_unknown_tokens() {
+    let input = "[Group] Title - 01 [1080p][FOOBAR][Dual Blah].mkv";
+    assert!(!anitomy::parse(input)
+        .iter()
+        .any(|e| e.kind() == ElementKind::Unknown));
+
+    let options = anitomy::Options::default().unknown_tokens(true);
+    let unknown: Vec<_> = anitomy::parse_with_options(input, options)
+        .into_iter()
+        .filter(|e| e.kind() == ElementKind::Unknown)
+        .map(|e| (e.value().to_owned(), e.span()))
+        .collect();
+    assert_eq!(
+        unknown,
+        [
+            ("FOOBAR".to_owned(), 27..33),
+            ("Dual".to_owned(), 35..39),
+            ("Blah".to_owned(), 40..44),
+        ]
+    );
+
+    let tests: Vec<InputData> = test_data();
+    for test in &tests {
+        let options: anitomy::Options = test.options.clone().into();
+        let known: Vec<_> = anitomy::parse_with_options(&test.input, options.unknown_tokens(true))
+            .into_iter()
+            .filter(|e| e.kind() != ElementKind::Unknown)
+            .collect();
+        assert_eq!(known, anitomy::parse_with_options(&test.input, options));
+    }
+}
+
+#[test]
+fn test_scene_names() {
+    let elements = |input: &str, options: anitomy::Options| {
+        anitomy::parse_with_options(input, options)
+            .into_iter()
+            .map(|e| (e.kind(), e.value().to_owned()))
+            .collect::<Vec<_>>()
+    };
+    let owned = |elements: &[(ElementKind, &str)]| {
+        elements
+            .iter()
+            .map(|(kind, value)| (*kind, value.to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        elements(
+            "Show.Name.S02E05.PROPER.1080p.AMZN.WEB-DL.DDP5.1.H.264-GROUP.mkv",
+            Default::default()
+        ),
+        owned(&[
+            (ElementKind::Title, "Show Name"),
+            (ElementKind::Season, "02"),
+            (ElementKind::Episode, "05"),
+            (ElementKind::ReleaseInformation, "PROPER"),
+            (ElementKind::VideoResolution, "1080p"),
+            (ElementKind::Source, "AMZN"),
+            (ElementKind::Source, "WEB-DL"),
+            (ElementKind::AudioTerm, "DDP5.1"),
+            (ElementKind::VideoTerm, "H.264"),
+            (ElementKind::ReleaseGroup, "GROUP"),
+            (ElementKind::FileExtension, "mkv"),
+        ])
+    );
+    assert_eq!(
+        elements(
+            "Show.Name.S01E01-E03.720p.WEB.h264-GROUP[rarbg]",
+            Default::default()
+        ),
+        owned(&[
+            (ElementKind::Title, "Show Name"),
+            (ElementKind::Season, "01"),
+            (ElementKind::Episode, "01"),
+            (ElementKind::Episode, "03"),
+            (ElementKind::VideoResolution, "720p"),
+            (ElementKind::Source, "WEB"),
+            (ElementKind::VideoTerm, "h264"),
+            (ElementKind::ReleaseGroup, "GROUP"),
+        ])
+    );
+    assert_eq!(
+        elements(
+            "Movie.Name.2019.1080p.BluRay.x264-GROUP.mkv",
+            Default::default()
+        ),
+        owned(&[
+            (ElementKind::Title, "Movie Name"),
+            (ElementKind::Year, "2019"),
+            (ElementKind::VideoResolution, "1080p"),
+            (ElementKind::Source, "BluRay"),
+            (ElementKind::VideoTerm, "x264"),
+            (ElementKind::ReleaseGroup, "GROUP"),
+            (ElementKind::FileExtension, "mkv"),
+        ])
+    );
+
+    // Release information between the year and the marker is neither the title nor a number
+    for info in ["REPACK", "PROPER"] {
+        assert_eq!(
+            elements(
+                &format!("Show.Name.2019.{info}.1080p.BluRay.x264-GROUP.mkv"),
+                Default::default()
+            ),
+            owned(&[
+                (ElementKind::Title, "Show Name"),
+                (ElementKind::Year, "2019"),
+                (ElementKind::ReleaseInformation, info),
+                (ElementKind::VideoResolution, "1080p"),
+                (ElementKind::Source, "BluRay"),
+                (ElementKind::VideoTerm, "x264"),
+                (ElementKind::ReleaseGroup, "GROUP"),
+                (ElementKind::FileExtension, "mkv"),
+            ])
+        );
+    }
+
+    // `Internal` and `Proper` are only release information in scene names
+    assert_eq!(
+        elements("Internal Affairs - 01 [720p].mkv", Default::default()),
+        owned(&[
+            (ElementKind::Title, "Internal Affairs"),
+            (ElementKind::Episode, "01"),
+            (ElementKind::VideoResolution, "720p"),
+            (ElementKind::FileExtension, "mkv"),
+        ])
+    );
+    assert_eq!(
+        elements("[Group] A Proper Title - 01.mkv", Default::default()),
+        owned(&[
+            (ElementKind::ReleaseGroup, "Group"),
+            (ElementKind::Title, "A Proper Title"),
+            (ElementKind::Episode, "01"),
+            (ElementKind::FileExtension, "mkv"),
+        ])
+    );
+
+    // Without the `-GROUP` suffix the name is only parsed as a scene release when forced to
+    let input = "Show.Name.S01E01E02.1080p.WEB.mkv";
+    let options = anitomy::Options::default().scene(true);
+    assert!(!elements(input, Default::default()).contains(&(ElementKind::Episode, "02".into())));
+    assert_eq!(
+        elements(input, options),
+        owned(&[
+            (ElementKind::Title, "Show Name"),
+            (ElementKind::Season, "01"),
+            (ElementKind::Episode, "01"),
+            (ElementKind::Episode, "02"),
+            (ElementKind::VideoResolution, "1080p"),
+            (ElementKind::Source, "WEB"),
+            (ElementKind::FileExtension, "mkv"),
+        ])
+    );
+    let episode = anitomy::parse_with_options(input, options)
+        .into_iter()
+        .rfind(|e| e.kind() == ElementKind::Episode)
+        .unwrap();
+    assert_eq!(episode.rule(), Rule::SceneMultiEpisode);
+    assert_eq!(&input[episode.span()], "02");
+}
+
+#[test]
+fn test_alternative_titles() {
+    let titles_with_options = |input: &str, options: Options| {
+        anitomy::parse_with_options(input, options)
+            .into_iter()
+            .filter(|e| matches!(e.kind(), ElementKind::Title | ElementKind::AlternativeTitle))
+            .map(|e| (e.kind(), e.value().to_owned()))
+            .collect::<Vec<_>>()
+    };
+    let titles = |input: &str| titles_with_options(input, Options::default());
+    let expected = |title: &str, alternatives: &[&str]| {
+        std::iter::once((ElementKind::Title, title.to_owned()))
+            .chain(
+                alternatives
+                    .iter()
+                    .map(|value| (ElementKind::AlternativeTitle, value.to_string())),
+            )
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        titles("[Group] Shingeki no Kyojin / Attack on Titan - 01 [1080p].mkv"),
+        expected("Shingeki no Kyojin", &["Attack on Titan"])
+    );
+    assert_eq!(
+        titles("Title A | Title B | Title C - 02.mkv"),
+        expected("Title A", &["Title B", "Title C"])
+    );
+    assert_eq!(
+        titles("[Group] 進撃の巨人 (Attack on Titan) - 01 [1080p].mkv"),
+        expected("進撃の巨人", &["Attack on Titan"])
+    );
+    assert_eq!(
+        titles("[Group] 鬼滅の刃 [Demon Slayer] - 01 [1080p].mkv"),
+        expected("鬼滅の刃", &["Demon Slayer"])
+    );
+    assert_eq!(
+        titles("Yes! Precure 5 GoGo! (Yes! Pretty Cure 5 GoGo!) - 01.mkv"),
+        expected("Yes! Precure 5 GoGo!", &["Yes! Pretty Cure 5 GoGo!"])
+    );
+
+    // Brackets in the same script are only split in torrent titles
+    assert_eq!(
+        titles("[Group] Girls und Panzer (Das Finale) - 01.mkv"),
+        expected("Girls und Panzer (Das Finale)", &[])
+    );
+    assert_eq!(
+        titles("[Group] Shingeki no Kyojin (Attack on Titan) - 01 [1080p].mkv"),
+        expected("Shingeki no Kyojin (Attack on Titan)", &[])
+    );
+    assert_eq!(
+        titles_with_options(
+            "[Group] Shingeki no Kyojin (Attack on Titan) - 01 [1080p].mkv",
+            Options::default().torrent(true)
+        ),
+        expected("Shingeki no Kyojin", &["Attack on Titan"])
+    );
+    assert_eq!(
+        titles_with_options(
+            "[Group] Kimetsu no Yaiba [Demon Slayer] - 01 [1080p].mkv",
+            Options::default().torrent(true)
+        ),
+        expected("Kimetsu no Yaiba", &["Demon Slayer"])
+    );
+
+    // A slash inside a word and a single word in parentheses are part of the title
+    assert_eq!(
+        titles("[Group] Fate/Zero - 01 [1080p].mkv"),
+        expected("Fate/Zero", &[])
+    );
+    assert_eq!(
+        titles("[Group] Nekomonogatari (Black) - 01.mkv"),
+        expected("Nekomonogatari (Black)", &[])
+    );
+
+    // A title that is only a separator is kept, rather than becoming the episode title
+    let elements = anitomy::parse("[Group] / - 01.mkv");
+    assert!(elements
+        .iter()
+        .any(|e| e.kind() == ElementKind::Title && e.value() == "/"));
+    assert!(!elements
+        .iter()
+        .any(|e| e.kind() == ElementKind::EpisodeTitle));
+
+    let element = anitomy::parse("Title A / Title B - 01.mkv")
+        .into_iter()
+        .find(|e| e.kind() == ElementKind::AlternativeTitle)
+        .unwrap();
+    assert_eq!(element.rule(), Rule::AlternativeTitle);
+    assert_eq!(&"Title A / Title B - 01.mkv"[element.span()], "Title B");
+}
+
+#[test]
+fn test_totals() {
+    let elements = |input: &str| {
+        anitomy::parse(input)
+            .into_iter()
+            .filter(|e| {
+                matches!(
+                    e.kind(),
+                    ElementKind::Episode
+                        | ElementKind::EpisodeTotal
+                        | ElementKind::Volume
+                        | ElementKind::VolumeTotal
+                )
+            })
+            .map(|e| (e.kind(), e.value().to_owned(), e.rule()))
+            .collect::<Vec<_>>()
+    };
+    let owned = |elements: &[(ElementKind, &str, Rule)]| {
+        elements
+            .iter()
+            .map(|(kind, value, rule)| (*kind, value.to_string(), *rule))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        elements("Noein_[01_of_24]_[ru_jp].mkv"),
+        owned(&[
+            (ElementKind::Episode, "01", Rule::NumberInNumber),
+            (ElementKind::EpisodeTotal, "24", Rule::NumberInNumber),
+        ])
+    );
+    assert_eq!(
+        elements("[Group] Title - 03/12 [1080p].mkv"),
+        owned(&[
+            (ElementKind::Episode, "03", Rule::NumberOfTotal),
+            (ElementKind::EpisodeTotal, "12", Rule::NumberOfTotal),
+        ])
+    );
+    assert_eq!(
+        elements("[Group] Title 全12話 第03話.mkv"),
+        owned(&[
+            (ElementKind::EpisodeTotal, "12", Rule::TotalJapanese),
+            (ElementKind::Episode, "03", Rule::EpisodeJapanese),
+        ])
+    );
+    assert_eq!(
+        elements("[Group] Title Vol. 3 of 5 [1080p].mkv"),
+        owned(&[
+            (ElementKind::Volume, "3", Rule::Volume),
+            (ElementKind::VolumeTotal, "5", Rule::Volume),
+        ])
+    );
+    assert_eq!(
+        elements("[Group] Title 全5巻 [BD].mkv"),
+        owned(&[(ElementKind::VolumeTotal, "5", Rule::TotalJapanese)])
+    );
+
+    // A number larger than the total isn't an episode, e.g. a date
+    assert!(elements("[Group] Title 12/03 [1080p].mkv").is_empty());
+
+    // A number and the total in the title is only the episode if nothing else could be
+    for (input, title, episode) in [
+        ("[Group] Ranma 1/2 - 05 [720p].mkv", "Ranma 1/2", "05"),
+        ("[Group] 1/2 Prince - 01.mkv", "1/2 Prince", "01"),
+    ] {
+        let elements: Vec<_> = anitomy::parse(input)
+            .into_iter()
+            .filter(|e| {
+                matches!(
+                    e.kind(),
+                    ElementKind::Title | ElementKind::Episode | ElementKind::EpisodeTotal
+                )
+            })
+            .map(|e| (e.kind(), e.value().to_owned()))
+            .collect();
+        assert_eq!(
+            elements,
+            [
+                (ElementKind::Title, title.to_owned()),
+                (ElementKind::Episode, episode.to_owned()),
+            ],
+            "{input}"
+        );
+    }
+    let titles: Vec<_> = anitomy::parse("Ranma 1/2.mkv")
+        .into_iter()
+        .filter(|e| e.kind() != ElementKind::FileExtension)
+        .map(|e| (e.kind(), e.value().to_owned()))
+        .collect();
+    assert_eq!(titles, [(ElementKind::Title, "Ranma 1/2".to_owned())]);
+    assert!(elements("[Group] Title 03/12 [1080p].mkv").is_empty());
+    assert_eq!(
+        elements("[Group] Title Episode 03/12 [1080p].mkv"),
+        owned(&[
+            (ElementKind::Episode, "03", Rule::NumberOfTotal),
+            (ElementKind::EpisodeTotal, "12", Rule::NumberOfTotal),
+        ])
+    );
+    assert_eq!(
+        elements("[Group] 86 Title [03/12] [1080p].mkv"),
+        owned(&[
+            (ElementKind::Episode, "03", Rule::NumberOfTotal),
+            (ElementKind::EpisodeTotal, "12", Rule::NumberOfTotal),
+        ])
+    );
+}
+
+#[test]
+fn test_torrent_titles() {
+    let options = anitomy::Options::default().torrent(true);
+    let elements = |input: &str| {
+        anitomy::parse_with_options(input, options)
+            .into_iter()
+            .map(|e| (e.kind(), e.value().to_owned()))
+            .collect::<Vec<_>>()
+    };
+    let owned = |elements: &[(ElementKind, &str)]| {
+        elements
+            .iter()
+            .map(|(kind, value)| (*kind, value.to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        elements("[Group] Shingeki no Kyojin / Attack on Titan - 01-25 [1080p] [Batch]"),
+        owned(&[
+            (ElementKind::ReleaseGroup, "Group"),
+            (ElementKind::Title, "Shingeki no Kyojin"),
+            (ElementKind::AlternativeTitle, "Attack on Titan"),
+            (ElementKind::Episode, "01"),
+            (ElementKind::Episode, "25"),
+            (ElementKind::VideoResolution, "1080p"),
+            (ElementKind::ReleaseInformation, "Batch"),
+        ])
+    );
+    assert_eq!(
+        elements("[Group] Title | Alt Title - 05 [1080p][HEVC] | 1.4 GiB"),
+        owned(&[
+            (ElementKind::ReleaseGroup, "Group"),
+            (ElementKind::Title, "Title"),
+            (ElementKind::AlternativeTitle, "Alt Title"),
+            (ElementKind::Episode, "05"),
+            (ElementKind::VideoResolution, "1080p"),
+            (ElementKind::VideoTerm, "HEVC"),
+        ])
+    );
+    assert_eq!(
+        elements("Title + Alt Title (01-12) [BD 1080p] [Complete] 12.5GB"),
+        owned(&[
+            (ElementKind::Title, "Title"),
+            (ElementKind::AlternativeTitle, "Alt Title"),
+            (ElementKind::Episode, "01"),
+            (ElementKind::Episode, "12"),
+            (ElementKind::Source, "BD"),
+            (ElementKind::VideoResolution, "1080p"),
+            (ElementKind::ReleaseInformation, "Complete"),
+        ])
+    );
+    assert_eq!(
+        elements("[Group] Title S2 - 01 ~ 12 [1080p] (Batch)"),
+        owned(&[
+            (ElementKind::ReleaseGroup, "Group"),
+            (ElementKind::Title, "Title"),
+            (ElementKind::Season, "2"),
+            (ElementKind::Episode, "01"),
+            (ElementKind::Episode, "12"),
+            (ElementKind::VideoResolution, "1080p"),
+            (ElementKind::ReleaseInformation, "Batch"),
+        ])
+    );
+
+    // The size isn't mistaken for the release group
+    assert_eq!(
+        elements("Title / タイトル - 03 (WEB 1080p) [2.1 GB]"),
+        owned(&[
+            (ElementKind::Title, "Title"),
+            (ElementKind::AlternativeTitle, "タイトル"),
+            (ElementKind::Episode, "03"),
+            (ElementKind::Source, "WEB"),
+            (ElementKind::VideoResolution, "1080p"),
+        ])
+    );
+
+    // Unlike in file names, `+` separates titles
+    assert!(anitomy::parse("Title + Alt Title - 01.mkv")
+        .iter()
+        .all(|e| e.kind() != ElementKind::AlternativeTitle));
+}
+
+#[test]
+fn test_confidence() {
+    use anitomy::Confidence;
+
+    let confidence = |input: &str, kind: ElementKind| {
+        anitomy::parse(input)
+            .into_iter()
+            .find(|e| e.kind() == kind)
+            .map(|e| e.confidence())
+    };
+
+    let input = "[Group] Title - 01 [1080p][1234ABCD].mkv";
+    assert_eq!(
+        confidence(input, ElementKind::FileChecksum),
+        Some(Confidence::High)
+    );
+    assert_eq!(
+        confidence(input, ElementKind::Episode),
+        Some(Confidence::Medium)
+    );
+    assert_eq!(
+        confidence("Title 1080 - 01", ElementKind::VideoResolution),
+        Some(Confidence::Low)
+    );
+    assert_eq!(
+        confidence("Title S01E02 1080p-GROUP", ElementKind::ReleaseGroup),
+        Some(Confidence::Low)
+    );
+    assert_eq!(
+        confidence("Title.S01E02.1080p-GROUP", ElementKind::ReleaseGroup),
+        Some(Confidence::High)
+    );
+    assert!(Confidence::High > Confidence::Medium && Confidence::Medium > Confidence::Low);
+}
+
+#[test]
+fn test_parse_candidates() {
+    let summary = |elements: &[anitomy::Element<'_>]| {
+        elements
+            .iter()
+            .map(|e| (e.kind(), e.value().to_owned()))
+            .collect::<Vec<_>>()
+    };
+
+    let candidates = anitomy::parse_candidates("86 - 01", 5);
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].elements, anitomy::parse("86 - 01"));
+    assert_eq!(
+        summary(&candidates[1].elements),
+        [(ElementKind::Title, "86 - 01".to_owned())]
+    );
+    assert!(candidates[0].score > candidates[1].score);
+
+    let candidates = anitomy::parse_candidates("Mob Psycho 100 - 12", 5);
+    assert_eq!(
+        candidates[0].elements,
+        anitomy::parse("Mob Psycho 100 - 12")
+    );
+    assert!(candidates
+        .iter()
+        .any(|c| summary(&c.elements).starts_with(&[
+            (ElementKind::Title, "Mob Psycho".to_owned()),
+            (ElementKind::Episode, "100".to_owned()),
+        ])));
+
+    let candidates = anitomy::parse_candidates("Title 2019 - 05.mkv", 5);
+    assert!(candidates.iter().any(|c| summary(&c.elements)
+        == [
+            (ElementKind::Title, "Title".to_owned()),
+            (ElementKind::Year, "2019".to_owned()),
+            (ElementKind::Episode, "05".to_owned()),
+            (ElementKind::FileExtension, "mkv".to_owned()),
+        ]));
+    let candidates = anitomy::parse_candidates("[Group] Title - 2019", 5);
+    assert!(candidates.iter().any(|c| summary(&c.elements)
+        == [
+            (ElementKind::ReleaseGroup, "Group".to_owned()),
+            (ElementKind::Title, "Title".to_owned()),
+            (ElementKind::Year, "2019".to_owned()),
+        ]));
+
+    let candidates = anitomy::parse_candidates("Title S01E02 ESP", 1);
+    assert_eq!(candidates.len(), 1);
+    assert!(anitomy::parse_candidates("Title S01E02 ESP", 0).is_empty());
+
+    let tests: Vec<InputData> = test_data();
+    for test in &tests {
+        let options = test.options.clone().into();
+        let parsed = anitomy::parse_with_options(&test.input, options);
+        let candidates = anitomy::parse_candidates_with_options(&test.input, options, 10);
+        assert!(
+            candidates.iter().any(|c| c.elements == parsed),
+            "{}",
+            test.input
+        );
+        assert!(candidates.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+}
+
+#[test]
+fn test_multi_element_object() {
+    use anitomy::{MultiElementObject, OwnedMultiElementObject};
+
+    #[derive(Deserialize)]
+    struct Output {
+        output: serde_json::Value,
+    }
+
+    // Every expected output is in the multi-value format and has to round-trip
+    let tests: Vec<Output> = test_data();
+    for test in tests {
+        let object: OwnedMultiElementObject =
+            serde_json::from_value(test.output.clone()).expect("could not parse output");
+        assert_eq!(serde_json::to_value(&object).unwrap(), test.output);
+    }
+
+    let elements = anitomy::parse("[Group] Title - 01-12 [BD 1080p FLAC AAC][ENG][JPN].mkv");
+    let object: MultiElementObject = elements.iter().collect();
+    assert_eq!(object.episode, ["01", "12"]);
+    assert_eq!(object.audio_term, ["FLAC", "AAC"]);
+    assert_eq!(object.language, ["ENG", "JPN"]);
+    assert_eq!(object.source, ["BD"]);
+    assert_eq!(object.title.as_deref(), Some("Title"));
+    assert_eq!(object, elements.clone().into_iter().collect());
+
+    let json = serde_json::to_string(&object).unwrap();
+    assert!(json.contains(r#""episode":["01","12"]"#), "{json}");
+    assert!(json.contains(r#""source":"BD""#), "{json}");
+    assert!(!json.contains("video_term"), "{json}");
+    let borrowed: MultiElementObject = serde_json::from_str(&json).unwrap();
+    assert_eq!(borrowed, object);
+    let owned: OwnedMultiElementObject = serde_json::from_str(&json).unwrap();
+    assert_eq!(owned, elements.iter().collect());
+}
+
+#[test]
+fn test_template_round_trip() {
+    let template: anitomy::Template = "{?release_group:[{release_group}] }{title}{?season: S{season:02}} - {episode:02}{?episode_title: - {episode_title}} [{video_resolution}].{file_extension}"
+        .parse()
+        .expect("could not parse template");
+    let cases = [
+        (
+            "[TaigaSubs]_Toradora!_(2008)_-_01v2_-_Tiger_and_Dragon_[1280x720_H.264_FLAC][1234ABCD].mkv",
+            "[TaigaSubs] Toradora! - 01 - Tiger and Dragon [1280x720].mkv",
+        ),
+        (
+            "[HorribleSubs] Tower of Druaga - Sword of Uruk - 04 [480p].mkv",
+            "[HorribleSubs] Tower of Druaga - Sword of Uruk - 04 [480p].mkv",
+        ),
+        (
+            "Noragami S2 - 6 [1080p].mkv",
+            "Noragami S02 - 06 [1080p].mkv",
+        ),
+        (
+            "[Group] Title Season 2 - 01-12 [720p].mkv",
+            "[Group] Title S02 - 01-12 [720p].mkv",
+        ),
+        (
+            "Title - 12.5 [1080p].mkv",
+            "Title - 12.5 [1080p].mkv",
+        ),
+    ];
+
+    for (input, expected) in cases {
+        let formatted = template.render(&anitomy::parse(input));
+        assert_eq!(formatted, expected);
+        // Formatting is stable once a name is in the format of the template
+        assert_eq!(template.render(&anitomy::parse(&formatted)), formatted);
+    }
+}
+
+#[test]
+fn test_parse_batch() {
+    let inputs = [
+        "[Group] 22-7 - 01 [1080p].mkv",
+        "[Group] 22-7 - 02 [1080p].mkv",
+        "Group 22-7 - 03 [1080p].mkv",
+        "[Group] 22-7 - 04 [1080p].mkv",
+        "[Other] Another Show - 01.mkv",
+    ];
+    let batch = anitomy::parse_batch(&inputs);
+    assert_eq!(batch.len(), inputs.len());
+    assert!(!batch[0].is_corrected());
+    assert_eq!(batch[0].elements, anitomy::parse(inputs[0]));
+
+    let corrected = &batch[2];
+    assert_eq!(
+        corrected.corrections,
+        [ElementKind::ReleaseGroup, ElementKind::Title]
+    );
+    let find = |kind| {
+        corrected
+            .elements
+            .iter()
+            .find(|e| e.kind() == kind)
+            .unwrap()
+    };
+    let release_group = find(ElementKind::ReleaseGroup);
+    assert_eq!(release_group.value(), "Group");
+    assert_eq!(release_group.rule(), Rule::BatchConsensus);
+    assert_eq!(&inputs[2][release_group.span()], "Group");
+    assert_eq!(&inputs[2][find(ElementKind::Title).span()], "22-7");
+    assert_eq!(find(ElementKind::Episode).value(), "03");
+
+    // Names of another series don't have to agree with the batch
+    assert!(!batch[4].is_corrected());
+
+    let inputs = ["Show.2021.1080p.mkv", "Show.2022.1080p.mkv"];
+    for entry in anitomy::parse_batch(&inputs) {
+        assert_eq!(entry.elements, anitomy::parse(entry.input));
+        assert!(entry.corrections.is_empty());
+        let values: Vec<_> = entry.elements.iter().map(|e| e.value()).collect();
+        assert_eq!(values[..2], ["Show", &entry.input[5..9]]);
+    }
+}
+
+#[test]
+fn test_parse_path() {
+    let parse = |path: &str| {
+        let parsed = anitomy::parse_path(path);
+        parsed
+            .elements
+            .iter()
+            .map(|e| {
+                let element = &e.element;
+                assert_eq!(path[element.span()].replace('.', " "), element.value());
+                (element.kind(), element.value().to_owned(), e.component)
+            })
+            .collect::<Vec<_>>()
+    };
+    let owned = |elements: &[(ElementKind, &str, usize)]| {
+        elements
+            .iter()
+            .map(|(kind, value, component)| (*kind, value.to_string(), *component))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        parse("Show Name (2019)/Season 2/05 - Episode Title.mkv"),
+        owned(&[
+            (ElementKind::Title, "Show Name", 0),
+            (ElementKind::Year, "2019", 0),
+            (ElementKind::Season, "2", 1),
+            (ElementKind::Episode, "05", 2),
+            (ElementKind::EpisodeTitle, "Episode Title", 2),
+            (ElementKind::FileExtension, "mkv", 2),
+        ])
+    );
+    assert_eq!(
+        parse("/anime/Show Name/S02E05 - Episode Title.mkv"),
+        owned(&[
+            (ElementKind::Title, "Show Name", 1),
+            (ElementKind::Season, "02", 2),
+            (ElementKind::Episode, "05", 2),
+            (ElementKind::EpisodeTitle, "Episode Title", 2),
+            (ElementKind::FileExtension, "mkv", 2),
+        ])
+    );
+    // The file name has its own title, so only what it lacks comes from the directory
+    assert_eq!(
+        parse("Downloads/[Group] Show Name (2019) [1080p]/[Group] Show Name - 05.mkv"),
+        owned(&[
+            (ElementKind::Year, "2019", 1),
+            (ElementKind::VideoResolution, "1080p", 1),
+            (ElementKind::ReleaseGroup, "Group", 2),
+            (ElementKind::Title, "Show Name", 2),
+            (ElementKind::Episode, "05", 2),
+            (ElementKind::FileExtension, "mkv", 2),
+        ])
+    );
+    assert_eq!(
+        parse(r"C:\Anime\Show.Name.S02.1080p\Specials\02.mkv"),
+        owned(&[
+            (ElementKind::Title, "Show Name", 2),
+            (ElementKind::Season, "02", 2),
+            (ElementKind::VideoResolution, "1080p", 2),
+            (ElementKind::Type, "Specials", 3),
+            (ElementKind::Episode, "02", 4),
+            (ElementKind::FileExtension, "mkv", 4),
+        ])
+    );
+
+    // Without a title to come after, a file in a directory of a type is only an episode
+    assert_eq!(
+        parse("Specials/05.mkv"),
+        owned(&[
+            (ElementKind::Type, "Specials", 0),
+            (ElementKind::Episode, "05", 1),
+            (ElementKind::FileExtension, "mkv", 1),
+        ])
+    );
+
+    let parsed = anitomy::parse_path("Title - 01.mkv");
+    let elements: Vec<_> = parsed.elements().cloned().collect();
+    assert_eq!(elements, anitomy::parse("Title - 01.mkv"));
+    assert_eq!(parsed.file_name_component(), Some(0));
+    assert!(anitomy::parse_path("").elements.is_empty());
+}
@@ -0,0 +1,31 @@
+# Run with `maturin develop && pytest tests/test_python.py`
+import pytest
+
+import anitomy
+
+NAME = "[TaigaSubs]_Toradora!_(2008)_-_01v2_-_Tiger_and_Dragon_[1280x720_H.264_FLAC][1234ABCD].mkv"
+
+
+def test_parse():
+    result = anitomy.parse(NAME)
+    assert result["title"] == "Toradora!"
+    assert result["release_group"] == "TaigaSubs"
+    assert result["file_checksum"] == "1234ABCD"
+    assert "episode_title" not in anitomy.parse(NAME, episode_title=False)
+
+
+def test_parse_elements():
+    elements = anitomy.parse_elements("[Group] Title - 01-02 [1080p]")
+    assert ("episode", "01", (16, 18)) in elements
+    assert ("episode", "02", (19, 21)) in elements
+
+
+def test_parser():
+    parser = anitomy.Parser(year=False)
+    assert "year" not in parser.parse(NAME)
+    assert parser.parse_elements("86 - 01") == [("title", "86", (0, 2)), ("episode", "01", (5, 7))]
+
+
+def test_unknown_option():
+    with pytest.raises(TypeError):
+        anitomy.parse(NAME, bogus=True)